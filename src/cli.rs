@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -12,22 +15,111 @@ pub struct Cli {
 pub enum Command {
     /// Create a new worktree with an associated branch
     Open {
-        /// Branch name to create or use
-        branch: String,
+        /// Branch name to create or use (`pr/<n>` checks out a pull request)
+        #[arg(required_unless_present = "pr")]
+        branch: Option<String>,
 
         /// Skip confirmation prompts
         #[arg(short, long)]
         yes: bool,
+
+        /// Move uncommitted changes of the current checkout into the new worktree
+        #[arg(long, conflicts_with = "copy_changes")]
+        move_changes: bool,
+
+        /// Copy uncommitted changes of the current checkout into the new worktree
+        #[arg(long)]
+        copy_changes: bool,
+
+        /// Also carry untracked files (with --move-changes or --copy-changes)
+        #[arg(short = 'u', long)]
+        include_untracked: bool,
+
+        /// Check out a pull/merge request by number (branch defaults to pr-<n>)
+        #[arg(long, value_name = "NUMBER")]
+        pr: Option<u64>,
+
+        /// Remote to fetch the pull request from (default: origin)
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Fetch before resolving the branch (overrides the `fetch` setting)
+        #[arg(long, overrides_with = "no_fetch")]
+        fetch: bool,
+
+        /// Do not fetch before resolving the branch
+        #[arg(long, overrides_with = "fetch")]
+        no_fetch: bool,
+
+        /// Push a new branch and set its upstream
+        #[arg(long)]
+        push: bool,
     },
 
-    /// Remove a worktree (branch is kept)
+    /// Remove worktrees (branches are kept)
     Close {
-        /// Worktree name to close (defaults to current if inside a worktree)
-        worktree: Option<String>,
+        /// Worktree names or glob patterns to close (defaults to current if inside a worktree)
+        #[arg(conflicts_with = "all")]
+        worktrees: Vec<String>,
 
         /// Skip confirmation prompts
         #[arg(short, long)]
         yes: bool,
+
+        /// Close even if work could be lost: skips the safety checks and
+        /// discards uncommitted changes (after confirmation)
+        #[arg(short, long)]
+        force: bool,
+
+        /// Stash uncommitted changes, untracked files included, before closing
+        #[arg(long, conflicts_with = "save_patch")]
+        stash: bool,
+
+        /// Save uncommitted changes as a patch before closing
+        /// (relative paths are under .git/gwik/archive)
+        #[arg(long, value_name = "FILE")]
+        save_patch: Option<PathBuf>,
+
+        /// Move the worktree into the trash instead of deleting it (see `gwik restore`)
+        #[arg(long, conflicts_with_all = ["stash", "save_patch"])]
+        trash: bool,
+
+        /// Close every worktree besides the main checkout
+        #[arg(long)]
+        all: bool,
+
+        /// Only close worktrees whose branch is merged into the base branch
+        #[arg(long)]
+        merged: bool,
+
+        /// Only close worktrees whose last commit is older than this (e.g. 30d)
+        #[arg(long, value_name = "AGE", value_parser = crate::config::parse_duration)]
+        older_than: Option<Duration>,
+    },
+
+    /// Restore a worktree closed with --trash
+    Restore {
+        /// Worktree name (the most recently trashed one) or trash entry id
+        name: String,
+    },
+
+    /// Manage worktrees closed with --trash
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+
+    /// Rename a worktree together with its branch
+    Mv {
+        /// Worktree name to rename
+        old: String,
+
+        /// New branch name (the directory name is derived from it)
+        new: String,
+
+        /// Point the upstream at the new branch name on the same remote
+        #[arg(long)]
+        update_upstream: bool,
     },
 
     /// List worktrees
@@ -35,12 +127,129 @@ pub enum Command {
         /// List worktrees across all registered repositories
         #[arg(long)]
         all: bool,
+
+        /// Include the main checkout (source_worktree for bare repos)
+        #[arg(long)]
+        include_main: bool,
+
+        /// Group worktrees by repository, including the main checkout (with --all)
+        #[arg(long, requires = "all")]
+        group: bool,
+
+        /// Only repositories matching this glob pattern (with --all)
+        #[arg(long, value_name = "PATTERN", requires = "all")]
+        repo: Option<String>,
+
+        /// Only worktrees whose branch matches this glob pattern
+        #[arg(long, value_name = "PATTERN")]
+        branch: Option<String>,
+
+        /// Only worktrees with uncommitted changes or untracked files
+        #[arg(long)]
+        dirty: bool,
+
+        /// Only worktrees whose last commit is older than DAYS days
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u64>,
     },
 
     /// Output cd command to navigate to a worktree
     Cd {
-        /// Worktree name to navigate to
-        worktree: String,
+        /// Worktree name to navigate to (`@` for the main checkout, `-` for the previous one,
+        /// <repo>:<worktree> or <owner>/<repo>/<worktree> for other repositories)
+        #[arg(required_unless_present = "recent")]
+        worktree: Option<String>,
+
+        /// List visited worktrees across all repositories, ranked by frecency
+        #[arg(long, conflicts_with = "worktree")]
+        recent: bool,
+
+        /// Go to the worktree root instead of the current subdirectory
+        #[arg(long)]
+        root: bool,
+    },
+
+    /// Repair worktrees after the repository or worktree_dir was moved
+    Repair {
+        /// Only report problems (non-zero exit if any are found)
+        #[arg(long)]
+        check: bool,
+
+        /// Move worktrees into the configured worktree_dir without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Clone a repository as bare with a source worktree for its default branch
+    Clone {
+        /// Repository URL
+        url: String,
+
+        /// Destination directory (default: <first root>/<host>/<owner>/<repo>)
+        dir: Option<String>,
+    },
+
+    /// Convert a normal clone to the bare + worktrees layout (or back)
+    Convert {
+        /// Convert a bare repository back to a normal clone
+        #[arg(long)]
+        to_normal: bool,
+
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Diagnose environment, config and repository health
+    Doctor,
+
+    /// Show the status of every worktree in the repository
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rebase or merge every worktree branch onto its base
+    Sync {
+        /// Rebase branches onto the base (default)
+        #[arg(long, conflicts_with = "merge")]
+        rebase: bool,
+
+        /// Merge the base into branches
+        #[arg(long)]
+        merge: bool,
+
+        /// Ref to sync onto (defaults to origin/HEAD or the main checkout's branch)
+        #[arg(long, value_name = "REF")]
+        onto: Option<String>,
+
+        /// Show what would be updated without changing any branch
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Run a command in each worktree
+    Exec {
+        /// Run in worktrees across all registered repositories
+        #[arg(long)]
+        all: bool,
+
+        /// Also run in the main checkout
+        #[arg(long)]
+        include_main: bool,
+
+        /// Number of worktrees to run in parallel
+        #[arg(short = 'j', long, value_name = "N", default_value_t = 1)]
+        parallel: usize,
+
+        /// Only worktrees whose name matches this glob pattern (repeatable)
+        #[arg(long, value_name = "PATTERN")]
+        filter: Vec<String>,
+
+        /// Command to run, after `--`
+        #[arg(last = true, required = true)]
+        cmd: Vec<String>,
     },
 
     /// Output shell integration code
@@ -48,4 +257,24 @@ pub enum Command {
         /// Shell to generate integration for (bash, zsh)
         shell: String,
     },
+
+    /// Print completion candidates (used by shell integration)
+    #[command(hide = true)]
+    Complete {
+        /// Completion target (cd)
+        target: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// List trashed worktrees
+    List,
+
+    /// Delete trashed worktrees older than trash_expire (default: 30d)
+    Empty {
+        /// Delete all trashed worktrees
+        #[arg(long)]
+        all: bool,
+    },
 }
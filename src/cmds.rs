@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+#[cfg(unix)]
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::config::parse_duration;
+use crate::git::GitRepo;
+use crate::hooks::HookContext;
+use crate::pattern::glob_match;
+
+/// A post-creation command as written in gwik.toml: a plain string or a table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CmdEntry {
+    Plain(String),
+    Full(CmdSpec),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CmdSpec {
+    pub run: String,
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub timeout: Option<String>,
+    #[serde(default)]
+    pub continue_on_error: bool,
+    pub parallel_group: Option<String>,
+    #[serde(default)]
+    pub when: When,
+}
+
+/// Conditions for running a command; all given ones must hold
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct When {
+    /// Glob the branch name must match, e.g. "frontend/*"
+    pub branch: Option<String>,
+    /// Path, relative to the new worktree, that must exist
+    pub exists: Option<String>,
+}
+
+impl When {
+    pub fn matches(&self, branch: &str, worktree: &Path) -> bool {
+        self.branch
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, branch))
+            && self
+                .exists
+                .as_deref()
+                .is_none_or(|path| worktree.join(path).exists())
+    }
+}
+
+/// A post-creation command, ready to run
+#[derive(Debug, Clone, Default)]
+pub struct Cmd {
+    /// Shell command, run with `sh -c`
+    pub run: String,
+    /// "src", "dst" or a path relative to the new worktree (default: current directory)
+    pub cwd: Option<String>,
+    pub env: BTreeMap<String, String>,
+    /// Duration like "5m", checked when the command runs
+    pub timeout: Option<String>,
+    /// Warn instead of failing `gwik open`
+    pub continue_on_error: bool,
+    /// Consecutive commands in the same group run concurrently
+    pub parallel_group: Option<String>,
+    pub when: When,
+}
+
+impl From<CmdEntry> for Cmd {
+    fn from(entry: CmdEntry) -> Self {
+        match entry {
+            CmdEntry::Plain(run) => Cmd {
+                run,
+                ..Default::default()
+            },
+            CmdEntry::Full(spec) => Cmd {
+                run: spec.run,
+                cwd: spec.cwd,
+                env: spec.env,
+                timeout: spec.timeout,
+                continue_on_error: spec.continue_on_error,
+                parallel_group: spec.parallel_group,
+                when: spec.when,
+            },
+        }
+    }
+}
+
+/// Run post-creation commands in order, with consecutive commands of one
+/// parallel group running concurrently. Stops at the first failure unless
+/// the command has `continue_on_error`
+pub fn run_all(repo: &GitRepo, cmds: &[Cmd], ctx: &HookContext) -> Result<()> {
+    for batch in batches(cmds) {
+        let results: Vec<Result<()>> = if let [cmd] = batch {
+            vec![run_one(repo, cmd, ctx)]
+        } else {
+            thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|cmd| scope.spawn(|| run_one(repo, cmd, ctx)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap_or_else(|_| bail!("Command panicked")))
+                    .collect()
+            })
+        };
+
+        let mut failure = None;
+        for (cmd, result) in batch.iter().zip(results) {
+            match result {
+                Ok(()) => {}
+                Err(e) if cmd.continue_on_error => eprintln!("Warning: {:#}", e),
+                Err(e) => {
+                    failure.get_or_insert(e);
+                }
+            }
+        }
+        if let Some(e) = failure {
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Split commands into runs of one command, or of consecutive commands sharing a parallel group
+fn batches(cmds: &[Cmd]) -> Vec<&[Cmd]> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    for i in 1..=cmds.len() {
+        let same_group = i < cmds.len()
+            && cmds[i].parallel_group.is_some()
+            && cmds[i].parallel_group == cmds[i - 1].parallel_group;
+        if !same_group {
+            batches.push(&cmds[start..i]);
+            start = i;
+        }
+    }
+    batches
+}
+
+fn run_one(repo: &GitRepo, cmd: &Cmd, ctx: &HookContext) -> Result<()> {
+    // Checked here rather than when loading the config, which every command does
+    let timeout = match &cmd.timeout {
+        Some(timeout) => Some(
+            parse_duration(timeout)
+                .with_context(|| format!("Invalid timeout for `{}`", cmd.run))?,
+        ),
+        None => None,
+    };
+
+    eprintln!("Running: {}", cmd.run);
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&cmd.run)
+        // SRC and DST keep `$SRC`/`$DST` in older configs working
+        .env("SRC", ctx.src)
+        .env("DST", ctx.dst)
+        .envs(&cmd.env)
+        .stdin(Stdio::null())
+        // stdout is reserved for the cd line read by the shell wrapper
+        .stdout(io::stderr());
+    ctx.set_env(&mut command, repo);
+    if let Some(cwd) = &cmd.cwd {
+        let dir = match cwd.as_str() {
+            "src" => ctx.src.to_path_buf(),
+            "dst" => ctx.dst.to_path_buf(),
+            path => ctx.dst.join(path),
+        };
+        command.current_dir(dir);
+    }
+    // Its own process group lets a timeout kill everything the command started
+    #[cfg(unix)]
+    if timeout.is_some() {
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run command: {}", cmd.run))?;
+    #[cfg(unix)]
+    let _group = timeout.is_some().then(|| ProcessGroup::register(&child));
+    let status = match timeout {
+        Some(timeout) => match wait_with_timeout(&mut child, timeout)? {
+            Some(status) => status,
+            None => bail!(
+                "Post-creation command timed out after {}s: {}",
+                timeout.as_secs(),
+                cmd.run
+            ),
+        },
+        None => child.wait()?,
+    };
+
+    if !status.success() {
+        bail!("Post-creation command failed: {}", cmd.run);
+    }
+    Ok(())
+}
+
+/// Wait for the child, killing it after `timeout` (returns None then)
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            #[cfg(unix)]
+            signal_group(child.id(), "KILL");
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Send a signal to a process group, including commands started by the shell
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: &str) {
+    let _ = Command::new("kill")
+        .args([&format!("-{}", signal), "--", &format!("-{}", pgid)])
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Process groups of running commands
+#[cfg(unix)]
+static GROUPS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// A running command's own process group, which Ctrl-C in the terminal does not reach.
+/// While registered, Ctrl-C is forwarded to it before gwik exits
+#[cfg(unix)]
+struct ProcessGroup(u32);
+
+#[cfg(unix)]
+impl ProcessGroup {
+    fn register(child: &Child) -> Self {
+        static HANDLER: Once = Once::new();
+        HANDLER.call_once(|| {
+            let _ = ctrlc::set_handler(|| {
+                let groups = GROUPS.lock().unwrap_or_else(|e| e.into_inner());
+                for &pgid in groups.iter() {
+                    signal_group(pgid, "INT");
+                }
+                std::process::exit(130);
+            });
+        });
+
+        let pgid = child.id();
+        GROUPS.lock().unwrap_or_else(|e| e.into_inner()).push(pgid);
+        Self(pgid)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        GROUPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|&pgid| pgid != self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(run: &str, group: Option<&str>) -> Cmd {
+        Cmd {
+            run: run.to_string(),
+            parallel_group: group.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_batches() {
+        let cmds = vec![
+            cmd("a", None),
+            cmd("b", Some("deps")),
+            cmd("c", Some("deps")),
+            cmd("d", None),
+            cmd("e", None),
+            cmd("f", Some("deps")),
+        ];
+        let runs: Vec<Vec<&str>> = batches(&cmds)
+            .iter()
+            .map(|b| b.iter().map(|c| c.run.as_str()).collect())
+            .collect();
+        assert_eq!(
+            runs,
+            vec![vec!["a"], vec!["b", "c"], vec!["d"], vec!["e"], vec!["f"]]
+        );
+    }
+
+    #[test]
+    fn test_when() {
+        let dir = std::env::temp_dir();
+        assert!(When::default().matches("main", &dir));
+
+        let when = When {
+            branch: Some("frontend/*".to_string()),
+            exists: None,
+        };
+        assert!(when.matches("frontend/login", &dir));
+        assert!(!when.matches("infra/dns", &dir));
+
+        let when = When {
+            branch: Some("frontend/*".to_string()),
+            exists: Some("no-such-file-for-gwik".to_string()),
+        };
+        assert!(!when.matches("frontend/login", &dir));
+    }
+}
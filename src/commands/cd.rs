@@ -1,23 +1,49 @@
-use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
 
 use crate::config::Config;
-use crate::git::GitRepo;
+use crate::git::{GitRepo, WorktreeInfo};
+use crate::history::History;
+use crate::scanner::scan_repos;
+
+pub fn run(worktree: Option<&str>, recent: bool, root: bool) -> Result<()> {
+    if recent {
+        return run_recent();
+    }
+
+    let worktree = worktree.context("Specify a worktree name")?;
+    let nav = Navigator::new(root);
+
+    // `gwik cd -` goes back to the previously visited worktree
+    if worktree == "-" {
+        return run_previous(&nav);
+    }
+
+    // Qualified names (repo:worktree or owner/repo/worktree) are resolved across all roots,
+    // unless they name a worktree of the current repository (branch names contain slashes too)
+    if let Some((repo_query, name)) = split_qualified(worktree) {
+        if find_local(worktree).is_none() {
+            return run_qualified(&nav, repo_query, name);
+        }
+    }
 
-pub fn run(worktree: &str) -> Result<()> {
     let repo = GitRepo::detect()?;
     let config = Config::load(&repo.git_dir)?;
-    let worktrees = repo.list_worktrees()?;
 
-    // Try to find exact match by directory name
-    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
-    let target_path = worktree_dir.join(worktree);
-
-    // Check if this worktree exists
-    for wt in &worktrees {
-        if wt.path == target_path {
-            println!("cd {}", wt.path.display());
-            return Ok(());
+    // The main checkout (or source_worktree for bare repos)
+    if config.is_main_alias(worktree) {
+        if repo.is_bare && config.source_worktree.is_none() {
+            bail!("Bare repository has no main checkout. Set `source_worktree` in gwik.toml");
         }
+        let target_dir = repo.working_dir(config.source_worktree.as_deref());
+        return nav.navigate(&repo.root, &target_dir);
+    }
+
+    let worktrees = repo.list_worktrees()?;
+
+    if let Some(path) = local_match(&repo, &config, &worktrees, worktree) {
+        return nav.navigate(&repo.root, path);
     }
 
     // Not found - show available worktrees
@@ -33,3 +59,205 @@ pub fn run(worktree: &str) -> Result<()> {
 
     bail!("Worktree '{}' not found", worktree);
 }
+
+/// Find a worktree by directory name, or by branch name converted to one
+fn local_match<'a>(
+    repo: &GitRepo,
+    config: &Config,
+    worktrees: &'a [WorktreeInfo],
+    worktree: &str,
+) -> Option<&'a Path> {
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+    let target_path = worktree_dir.join(GitRepo::branch_to_dirname(worktree));
+
+    worktrees
+        .iter()
+        .find(|wt| wt.path == target_path)
+        .map(|wt| wt.path.as_path())
+}
+
+/// Look up a worktree of the repository containing the current directory
+fn find_local(worktree: &str) -> Option<PathBuf> {
+    let repo = GitRepo::detect().ok()?;
+    let config = Config::load(&repo.git_dir).ok()?;
+    let worktrees = repo.list_worktrees().ok()?;
+    local_match(&repo, &config, &worktrees, worktree).map(Path::to_path_buf)
+}
+
+/// Split a qualified worktree name into (repository query, worktree name)
+/// The path form needs both owner and repository, so a branch name like
+/// `feature/login` is not mistaken for one
+fn split_qualified(worktree: &str) -> Option<(&str, &str)> {
+    if let Some(split) = worktree.split_once(':') {
+        return Some(split);
+    }
+
+    worktree
+        .rsplit_once('/')
+        .filter(|(repo_query, _)| repo_query.contains('/'))
+}
+
+fn run_qualified(nav: &Navigator, repo_query: &str, name: &str) -> Result<()> {
+    if repo_query.is_empty() || name.is_empty() {
+        bail!("Invalid worktree name. Use <repo>:<worktree> or <owner>/<repo>/<worktree>");
+    }
+
+    let config = Config::load_global()?;
+    if config.roots.is_empty() {
+        bail!("No roots configured. Add `roots` to the global config to use <repo>:<worktree>");
+    }
+
+    let repos: Vec<_> = scan_repos(&config.roots)?
+        .into_iter()
+        .filter(|r| r.matches(repo_query))
+        .collect();
+
+    if repos.is_empty() {
+        bail!("Repository '{}' not found in roots", repo_query);
+    }
+
+    if config.is_main_alias(name) {
+        let [repo] = repos.as_slice() else {
+            eprintln!("Error: '{}' matches several repositories:", repo_query);
+            for repo in &repos {
+                eprintln!("  {}", repo.name);
+            }
+            bail!("Ambiguous repository '{}'", repo_query);
+        };
+        let Some(main) = &repo.main else {
+            bail!("Repository '{}' has no main checkout", repo.name);
+        };
+        return nav.navigate(&main.path, &main.path);
+    }
+
+    let matches: Vec<_> = repos
+        .iter()
+        .flat_map(|r| r.worktrees.iter().map(move |wt| (r, wt)))
+        .filter(|(_, wt)| wt.name() == name)
+        .collect();
+
+    match matches.as_slice() {
+        [(repo, wt)] => {
+            let repo_root = repo.main.as_ref().map_or(&wt.path, |m| &m.path);
+            nav.navigate(repo_root, &wt.path)
+        }
+        [] => {
+            eprintln!("Error: Worktree '{}' not found in '{}'", name, repo_query);
+            let available: Vec<_> = repos
+                .iter()
+                .flat_map(|r| r.worktrees.iter().map(move |wt| (r, wt)))
+                .map(|(r, wt)| format!("{}/{}", r.short_name(), wt.name()))
+                .collect();
+            if !available.is_empty() {
+                eprintln!("\nAvailable worktrees:");
+                for name in &available {
+                    eprintln!("  {}", name);
+                }
+            }
+            bail!("Worktree '{}' not found", name);
+        }
+        _ => {
+            eprintln!(
+                "Error: '{}' matches worktrees in several repositories:",
+                name
+            );
+            for (repo, _) in &matches {
+                eprintln!("  {}/{}", repo.name, name);
+            }
+            bail!("Ambiguous repository '{}'", repo_query);
+        }
+    }
+}
+
+fn run_previous(nav: &Navigator) -> Result<()> {
+    let history = History::load();
+    let current = nav.current.as_ref().map(|(_, checkout)| checkout.as_path());
+
+    let Some(visit) = history.previous(current) else {
+        bail!("No previous worktree in history");
+    };
+
+    nav.navigate(&visit.repo, &visit.path)
+}
+
+/// Print visited worktrees across all repositories, ranked by frecency
+fn run_recent() -> Result<()> {
+    for path in History::load().ranked() {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Where `gwik cd` is run from, and how it lands in the target
+struct Navigator {
+    /// Repository root and checkout containing the current directory
+    current: Option<(PathBuf, PathBuf)>,
+    /// Current directory relative to its checkout, if it should be preserved
+    subdir: Option<PathBuf>,
+}
+
+impl Navigator {
+    fn new(root: bool) -> Self {
+        let repo = GitRepo::detect().ok();
+        let current = repo.as_ref().and_then(|repo| {
+            let checkout = repo.current_checkout().ok().flatten()?;
+            Some((repo.root.clone(), checkout))
+        });
+
+        let keep_subdir = !root
+            && match &repo {
+                Some(repo) => Config::load(&repo.git_dir),
+                None => Config::load_global(),
+            }
+            .map(|c| c.keep_subdir)
+            .unwrap_or(true);
+
+        let subdir = if keep_subdir {
+            current
+                .as_ref()
+                .and_then(|(_, checkout)| relative_subdir(checkout))
+        } else {
+            None
+        };
+
+        Self { current, subdir }
+    }
+
+    /// Record the visit and output the cd command
+    fn navigate(&self, repo_root: &Path, target: &Path) -> Result<()> {
+        let mut history = History::load();
+
+        // Remember where we came from, unless gwik already brought us here
+        if let Some((current_repo, current)) = &self.current {
+            if current != target && history.last() != Some(current.as_path()) {
+                history.record(current_repo, current);
+            }
+        }
+
+        history.record(repo_root, target);
+        // History is best-effort and must not block navigation
+        let _ = history.save();
+
+        // Land in the same subdirectory when it exists in the target
+        let destination = match &self.subdir {
+            Some(subdir) if target.join(subdir).is_dir() => target.join(subdir),
+            _ => target.to_path_buf(),
+        };
+
+        println!("cd {}", destination.display());
+        Ok(())
+    }
+}
+
+/// Path of the current directory relative to the checkout root (None at the root)
+fn relative_subdir(checkout: &Path) -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?.canonicalize().ok()?;
+    let checkout = checkout.canonicalize().ok()?;
+    let subdir = cwd.strip_prefix(&checkout).ok()?;
+
+    if subdir.as_os_str().is_empty() {
+        None
+    } else {
+        Some(subdir.to_path_buf())
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::{self, Config};
+use crate::git::GitRepo;
+use crate::history;
+
+pub fn run(url: &str, dir: Option<&str>) -> Result<()> {
+    let dest = match dir {
+        Some(dir) => PathBuf::from(dir),
+        None => default_destination(url)?,
+    };
+
+    if dest.exists() && fs::read_dir(&dest)?.next().is_some() {
+        bail!(
+            "Destination {} already exists and is not empty",
+            dest.display()
+        );
+    }
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    eprintln!("Cloning {} into {}", url, dest.display());
+    let existed = dest.exists();
+    let repo = GitRepo::clone_bare(url, &dest)?;
+
+    // Remove the half-configured clone, so that the clone can simply be retried
+    let (source, source_path) = match set_up(&repo, url) {
+        Ok(source) => source,
+        Err(e) => {
+            if let Err(cleanup) = remove_clone(&dest, existed) {
+                eprintln!(
+                    "Warning: Failed to remove {}: {:#}",
+                    dest.display(),
+                    cleanup
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    eprintln!(
+        "Created bare repository at {} with source worktree {}",
+        repo.root.display(),
+        source
+    );
+
+    history::record_visit(&repo.root, &source_path);
+    println!("cd {}", source_path.display());
+
+    Ok(())
+}
+
+/// Configure a fresh bare clone and add its source worktree
+/// Returns the source worktree name and path
+fn set_up(repo: &GitRepo, url: &str) -> Result<(String, PathBuf)> {
+    // A bare clone maps branches directly onto refs/heads and fetches no remote-tracking refs
+    repo.config_set("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*")?;
+    repo.fetch_all()?;
+
+    let Some(branch) = repo.head_branch()? else {
+        bail!("Could not determine the default branch of {}", url);
+    };
+    repo.set_symbolic_ref(
+        "refs/remotes/origin/HEAD",
+        &format!("refs/remotes/origin/{}", branch),
+    )?;
+
+    // The default branch becomes the source worktree
+    let source = GitRepo::branch_to_dirname(&branch);
+    let source_path = repo.root.join(&source);
+    repo.create_worktree_existing_branch(&source_path, &branch)?;
+    repo.config_set(&format!("branch.{}.remote", branch), "origin")?;
+    repo.config_set(
+        &format!("branch.{}.merge", branch),
+        &format!("refs/heads/{}", branch),
+    )?;
+
+    fs::write(
+        config::local_config_path(&repo.git_dir),
+        format!("source_worktree = \"{}\"\n", source),
+    )
+    .context("Failed to write gwik.toml")?;
+
+    Ok((source, source_path))
+}
+
+/// Delete a clone, keeping the destination directory if it existed before
+fn remove_clone(dest: &Path, existed: bool) -> Result<()> {
+    if !existed {
+        return Ok(fs::remove_dir_all(dest)?);
+    }
+    for entry in fs::read_dir(dest)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Place the clone under the first root as host/owner/repo
+fn default_destination(url: &str) -> Result<PathBuf> {
+    let config = Config::load_global()?;
+    let Some(root) = config.roots.first() else {
+        bail!("No roots configured. Pass a directory or add `roots` to the global config");
+    };
+    let Some(path) = repo_path_from_url(url) else {
+        bail!(
+            "Cannot derive host/owner/repo from '{}'. Pass a directory",
+            url
+        );
+    };
+    Ok(root.join(path))
+}
+
+/// Convert a clone URL into a ghq-style relative path (host/owner/repo)
+fn repo_path_from_url(url: &str) -> Option<PathBuf> {
+    let (host, path) = if let Some(path) = url.strip_prefix("file://") {
+        // Local remotes have no host; keep only owner/repo
+        let mut segments = path.trim_end_matches('/').rsplit('/');
+        let repo = segments.next()?;
+        let owner = segments.next()?;
+        ("localhost", format!("{}/{}", owner, repo))
+    } else if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path.to_string())
+    } else {
+        // scp-like syntax: [user@]host:owner/repo
+        let (authority, path) = url.split_once(':')?;
+        let host = authority.rsplit('@').next()?;
+        (host, path.to_string())
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some(Path::new(host).join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_path_from_url() {
+        let cases = [
+            ("https://github.com/owner/repo.git", "github.com/owner/repo"),
+            ("https://github.com/owner/repo/", "github.com/owner/repo"),
+            (
+                "ssh://git@example.com:2222/owner/repo.git",
+                "example.com/owner/repo",
+            ),
+            ("git@github.com:owner/repo.git", "github.com/owner/repo"),
+            ("file:///tmp/remotes/owner/repo.git", "localhost/owner/repo"),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(
+                repo_path_from_url(url),
+                Some(PathBuf::from(expected)),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(repo_path_from_url("https://github.com/repo"), None);
+        assert_eq!(repo_path_from_url("repo"), None);
+    }
+}
@@ -1,16 +1,85 @@
-use std::io::{self, Write};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
 use crate::config::Config;
-use crate::git::GitRepo;
+use crate::git::{GitRepo, WorktreeInfo};
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::output::{format_age, render_table};
+use crate::pattern::{glob_match, is_glob};
+use crate::prompt::confirm;
+use crate::trash;
 
-pub fn run(worktree: Option<&str>, yes: bool) -> Result<()> {
+#[derive(Debug, Default)]
+pub struct CloseOptions {
+    /// Skip confirmation prompts
+    pub yes: bool,
+    /// Close despite safety checks, discarding uncommitted changes
+    pub force: bool,
+    /// Stash uncommitted changes (untracked files included) before closing
+    pub stash: bool,
+    /// Save uncommitted changes as a patch before closing
+    /// (relative paths are inside the archive dir)
+    pub save_patch: Option<PathBuf>,
+    /// Move the worktree into the trash instead of deleting it
+    pub trash: bool,
+}
+
+/// Which worktrees to close when closing several at once
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// Worktree names or glob patterns
+    pub names: Vec<String>,
+    /// Every worktree besides the main checkout
+    pub all: bool,
+    /// Only worktrees whose branch is merged into the base branch
+    pub merged: bool,
+    /// Only worktrees whose last commit is older than this
+    pub older_than: Option<Duration>,
+}
+
+impl Selection {
+    /// The worktree name when exactly one (or the current one, None) is meant
+    fn single(&self) -> Option<Option<&str>> {
+        if self.all || self.merged || self.older_than.is_some() {
+            return None;
+        }
+        match self.names.as_slice() {
+            [] => Some(None),
+            [name] if !is_glob(name) => Some(Some(name.as_str())),
+            _ => None,
+        }
+    }
+}
+
+/// How closing one worktree went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Closed {
+    Removed,
+    Trashed,
+    /// The user declined a confirmation
+    Aborted,
+}
+
+pub fn run(selection: &Selection, opts: &CloseOptions) -> Result<()> {
     let repo = GitRepo::detect()?;
     let config = Config::load(&repo.git_dir)?;
-    let worktrees = repo.list_worktrees()?;
 
-    let cwd = std::env::current_dir()?;
+    match selection.single() {
+        Some(worktree) => close_single(&repo, &config, worktree, opts),
+        None => close_many(&repo, &config, selection, opts),
+    }
+}
+
+fn close_single(
+    repo: &GitRepo,
+    config: &Config,
+    worktree: Option<&str>,
+    opts: &CloseOptions,
+) -> Result<()> {
+    let worktrees = repo.list_worktrees()?;
 
     // Determine target worktree
     let target_path = if let Some(name) = worktree {
@@ -43,36 +112,364 @@ pub fn run(worktree: Option<&str>, yes: bool) -> Result<()> {
         }
     };
 
-    // Check if we're inside the target worktree
-    let inside_target = cwd.starts_with(&target_path);
+    let target = worktrees
+        .iter()
+        .find(|wt| wt.path == target_path)
+        .context("Worktree not found")?;
+
+    if close_worktree(repo, config, target, opts, !opts.yes)? == Closed::Aborted {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    // Output cd command to main repository (or source_worktree for bare repos)
+    let target_dir = repo.working_dir(config.source_worktree.as_deref());
+    println!("cd {}", target_dir.display());
+
+    Ok(())
+}
+
+/// Close every selected worktree after one confirmation, reporting each result
+fn close_many(
+    repo: &GitRepo,
+    config: &Config,
+    selection: &Selection,
+    opts: &CloseOptions,
+) -> Result<()> {
+    if opts.save_patch.is_some() {
+        bail!("--save-patch closes a single worktree; use --stash or --trash for several");
+    }
+
+    let main = repo.main_worktree(config.source_worktree.as_deref())?;
+    let worktrees: Vec<WorktreeInfo> = repo
+        .list_worktrees()?
+        .into_iter()
+        .filter(|wt| main.as_ref().is_none_or(|m| m.path != wt.path))
+        .collect();
 
-    if inside_target && !yes {
-        eprint!("You are inside this worktree. Delete anyway? [y/N] ");
-        io::stderr().flush()?;
+    // A literal name that matches nothing is a typo; an empty glob is only worth a warning
+    let mut missing = Vec::new();
+    for name in &selection.names {
+        if !worktrees.iter().any(|wt| glob_match(name, &wt.name())) {
+            if is_glob(name) {
+                eprintln!("Warning: No worktree matches '{}'", name);
+            } else {
+                missing.push(name.as_str());
+            }
+        }
+    }
+    if !missing.is_empty() {
+        for name in &missing {
+            eprintln!("Error: Worktree '{}' not found", name);
+        }
+        if !worktrees.is_empty() {
+            eprintln!("\nAvailable worktrees:");
+            for wt in &worktrees {
+                eprintln!("  {}", wt.name());
+            }
+        }
+        bail!("Worktree(s) not found: {}", missing.join(", "));
+    }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = input.trim().to_lowercase();
+    let base = if selection.merged {
+        match repo.default_base(config.source_worktree.as_deref())? {
+            Some(base) => Some(base),
+            None => bail!("Cannot determine the base branch for --merged"),
+        }
+    } else {
+        None
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 
-        if input != "y" && input != "yes" {
-            eprintln!("Aborted.");
-            return Ok(());
+    let mut selected = Vec::new();
+    for wt in &worktrees {
+        let named =
+            selection.names.is_empty() || selection.names.iter().any(|n| glob_match(n, &wt.name()));
+        if !named {
+            continue;
+        }
+        if base.is_some() && wt.status(base.as_deref())?.merged != Some(true) {
+            continue;
         }
+        if let Some(age) = selection.older_than {
+            match wt.last_commit_time()? {
+                Some(time) if now.saturating_sub(time) >= age.as_secs() => {}
+                _ => continue,
+            }
+        }
+        selected.push(wt);
     }
 
-    // Remove the worktree
-    repo.remove_worktree(&target_path)?;
+    if selected.is_empty() {
+        eprintln!("No worktrees to close.");
+        return Ok(());
+    }
 
-    let worktree_name = target_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
+    // Confirmation table
+    let rows: Vec<[String; 4]> = selected
+        .iter()
+        .map(|wt| {
+            let changes = wt.changed_files().map(|c| c.len()).unwrap_or(0);
+            Ok([
+                wt.name(),
+                wt.branch
+                    .clone()
+                    .unwrap_or_else(|| "(detached)".to_string()),
+                wt.last_commit_time()?
+                    .map(|t| format_age(now.saturating_sub(t)))
+                    .unwrap_or_else(|| "-".to_string()),
+                if changes == 0 {
+                    "-".to_string()
+                } else {
+                    changes.to_string()
+                },
+            ])
+        })
+        .collect::<Result<_>>()?;
+    for line in render_table(&["WORKTREE", "BRANCH", "LAST COMMIT", "CHANGES"], &rows) {
+        eprintln!("{}", line);
+    }
+
+    // --force skips the per-worktree checks, so show what it throws away up front
+    if opts.force {
+        let keep_changes = opts.stash || opts.trash;
+        let mut losses = Vec::new();
+        for wt in &selected {
+            let mut lines = work_at_risk(repo, wt)?;
+            if !keep_changes {
+                lines.extend(wt.changed_files()?);
+            }
+            losses.extend(lines.into_iter().map(|line| (wt.name(), line)));
+        }
+        if !losses.is_empty() {
+            eprintln!("\nThis work will be lost:");
+            for (name, line) in &losses {
+                eprintln!("  {}: {}", name, line);
+            }
+        }
+    }
 
-    eprintln!("Removed worktree: {}", worktree_name);
+    let verb = if opts.trash { "Trash" } else { "Close" };
+    if !opts.yes && !confirm(&format!("{} {} worktree(s)?", verb, selected.len()))? {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
 
-    // Output cd command to main repository (or source_worktree for bare repos)
-    let target_dir = repo.working_dir(config.source_worktree.as_deref());
-    println!("cd {}", target_dir.display());
+    // One failure does not stop the rest
+    let cwd = std::env::current_dir()?;
+    let mut results = Vec::new();
+    let mut left_cwd = false;
+    for wt in &selected {
+        let result = match close_worktree(repo, config, wt, opts, false) {
+            Ok(Closed::Removed) => "removed".to_string(),
+            Ok(Closed::Trashed) => "moved to trash".to_string(),
+            Ok(Closed::Aborted) => "aborted".to_string(),
+            Err(e) => {
+                results.push([wt.name(), format!("failed: {}", e)]);
+                continue;
+            }
+        };
+        left_cwd |= cwd.starts_with(&wt.path);
+        results.push([wt.name(), result]);
+    }
+
+    eprintln!();
+    for line in render_table(&["WORKTREE", "RESULT"], &results) {
+        eprintln!("{}", line);
+    }
+
+    let failed = results
+        .iter()
+        .filter(|r| r[1].starts_with("failed"))
+        .count();
+    let summary = format!(
+        "{} of {} worktrees could not be closed",
+        failed,
+        selected.len()
+    );
+
+    // The shell wrapper only follows a cd on success, and the current
+    // directory is gone, so failures are reported without failing the command
+    if left_cwd {
+        if failed > 0 {
+            eprintln!("Warning: {}", summary);
+        }
+        let target_dir = repo.working_dir(config.source_worktree.as_deref());
+        println!("cd {}", target_dir.display());
+        return Ok(());
+    }
 
+    if failed > 0 {
+        bail!(summary);
+    }
     Ok(())
 }
+
+/// Close one worktree: safety checks, hooks, keeping or discarding changes
+/// `interactive` allows confirmation prompts
+fn close_worktree(
+    repo: &GitRepo,
+    config: &Config,
+    target: &WorktreeInfo,
+    opts: &CloseOptions,
+    interactive: bool,
+) -> Result<Closed> {
+    let target_path = &target.path;
+
+    // Refuse to drop work that exists nowhere else, unless forced
+    let risks = work_at_risk(repo, target)?;
+    if !risks.is_empty() && !opts.force {
+        for risk in &risks {
+            eprintln!("Warning: {}", risk);
+        }
+        bail!(
+            "Worktree '{}' has work that could be lost. Use --force to close anyway",
+            target.name()
+        );
+    }
+
+    // Uncommitted changes must be kept (--stash, --save-patch) or discarded (--force)
+    let changes = target.changed_files()?;
+    let patch_path = match opts.save_patch.as_deref() {
+        Some(file) => Some(patch_path(repo, file)?),
+        None => None,
+    };
+    if let Some(path) = patch_path.as_ref().filter(|p| p.exists()) {
+        bail!("{} already exists", path.display());
+    }
+    let keep_changes = opts.stash || patch_path.is_some() || opts.trash;
+    let discard = !changes.is_empty() && !keep_changes;
+    if discard && !opts.force {
+        bail!("Worktree has uncommitted changes. Use --stash, --save-patch <file> or --force");
+    }
+
+    // --force: show everything that will be lost, commits and stashes included
+    for risk in &risks {
+        eprintln!("Warning: {}", risk);
+    }
+    if discard && interactive {
+        eprintln!("These changes will be lost:");
+        for line in &changes {
+            eprintln!("  {}", line);
+        }
+        if !confirm("Close anyway?")? {
+            return Ok(Closed::Aborted);
+        }
+    }
+
+    // Check if we're inside the target worktree
+    let inside_target = std::env::current_dir()?.starts_with(target_path);
+
+    if inside_target && interactive && !confirm("You are inside this worktree. Delete anyway?")? {
+        return Ok(Closed::Aborted);
+    }
+
+    let target_dir = repo.working_dir(config.source_worktree.as_deref());
+    let hook_context = HookContext {
+        src: &target_dir,
+        dst: target_path,
+        branch: target.branch.as_deref(),
+    };
+    // A failing pre-close hook keeps the worktree
+    hooks::run(repo, &config.hooks, HookEvent::PreClose, &hook_context)?;
+
+    let mut force_remove = opts.force;
+    if !changes.is_empty() {
+        if opts.stash {
+            let message = format!("gwik close {}", target.name());
+            if let Some(stash) = target.stash_push(true, &message)? {
+                eprintln!(
+                    "Stashed changes as {} (restore with `git stash apply {}`)",
+                    &stash[..7],
+                    &stash[..7]
+                );
+            }
+        } else if let Some(path) = &patch_path {
+            save_patch(target, path)?;
+            eprintln!("Saved changes to {}", path.display());
+            force_remove = true;
+        }
+    }
+
+    let worktree_name = target.name();
+    let closed = if opts.trash {
+        trash::put(repo, target)?;
+        eprintln!(
+            "Moved worktree to trash: {} (restore with `gwik restore {}`)",
+            worktree_name, worktree_name
+        );
+        Closed::Trashed
+    } else {
+        repo.remove_worktree(target_path, force_remove)?;
+        eprintln!("Removed worktree: {}", worktree_name);
+        Closed::Removed
+    };
+    hooks::run(repo, &config.hooks, HookEvent::PostClose, &hook_context)?;
+
+    Ok(closed)
+}
+
+/// Where `--save-patch` puts relative patch files
+fn archive_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("gwik").join("archive")
+}
+
+/// Resolve a `--save-patch` file; relative paths must stay inside the archive dir
+fn patch_path(repo: &GitRepo, file: &Path) -> Result<PathBuf> {
+    if file.is_relative() && file.components().any(|c| c == Component::ParentDir) {
+        bail!(
+            "--save-patch paths cannot contain '..' ({}); use an absolute path to save elsewhere",
+            file.display()
+        );
+    }
+    Ok(archive_dir(repo).join(file))
+}
+
+fn save_patch(wt: &WorktreeInfo, path: &Path) -> Result<()> {
+    let patch = wt.changes_patch()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, patch).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reasons closing the worktree could lose work
+fn work_at_risk(repo: &GitRepo, wt: &WorktreeInfo) -> Result<Vec<String>> {
+    let mut risks = Vec::new();
+
+    match &wt.branch {
+        Some(branch) => {
+            // Without remotes every commit would count as unpushed
+            let unpushed = if repo.remotes()?.is_empty() {
+                0
+            } else {
+                wt.unpushed_commits()?
+            };
+            if unpushed > 0 {
+                risks.push(format!(
+                    "Branch '{}' has {} commit(s) not on any remote",
+                    branch, unpushed
+                ));
+            }
+            if let Some(&stashes) = repo.stash_counts()?.get(branch) {
+                risks.push(format!(
+                    "{} stash(es) were made on branch '{}'",
+                    stashes, branch
+                ));
+            }
+        }
+        None if wt.unreachable_commits()? > 0 => {
+            let head = wt.head.as_deref().unwrap_or_default();
+            risks.push(format!(
+                "HEAD is detached at {}, which is not reachable from any branch",
+                &head[..head.len().min(7)]
+            ));
+        }
+        None => {}
+    }
+
+    Ok(risks)
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::history::History;
+use crate::scanner::scan_repos;
+
+/// Print completion candidates for the shell integration, one per line
+pub fn run(target: &str) -> Result<()> {
+    match target {
+        "cd" => {
+            for candidate in worktree_candidates() {
+                println!("{}", candidate);
+            }
+            Ok(())
+        }
+        _ => bail!("Unknown completion target: {}", target),
+    }
+}
+
+/// `@` and worktree names in the current repository, then owner/repo/worktree across all roots
+/// Candidates are ordered by frecency, most relevant first
+fn worktree_candidates() -> Vec<String> {
+    let mut candidates: Vec<(PathBuf, String)> = Vec::new();
+
+    // Completion must never fail, so errors just yield fewer candidates
+    if let Ok(repo) = GitRepo::detect() {
+        if let Ok(config) = Config::load(&repo.git_dir) {
+            if !repo.is_bare || config.source_worktree.is_some() {
+                let main = repo.working_dir(config.source_worktree.as_deref());
+                candidates.push((main, "@".to_string()));
+            }
+        }
+        if let Ok(worktrees) = repo.list_worktrees() {
+            candidates.extend(worktrees.iter().map(|wt| (wt.path.clone(), wt.name())));
+        }
+    }
+
+    if let Ok(config) = Config::load_global() {
+        if let Ok(repos) = scan_repos(&config.roots) {
+            for repo in &repos {
+                candidates.extend(repo.worktrees.iter().map(|wt| {
+                    let name = format!("{}/{}", repo.short_name(), wt.name());
+                    (wt.path.clone(), name)
+                }));
+            }
+        }
+    }
+
+    let ranked = History::load().ranked();
+    let rank = |path: &PathBuf| ranked.iter().position(|p| p == path).unwrap_or(usize::MAX);
+    candidates.sort_by_key(|(path, _)| rank(path));
+
+    candidates.into_iter().map(|(_, name)| name).collect()
+}
@@ -0,0 +1,459 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::{self, Config};
+use crate::files::move_entries;
+use crate::git::{read_gitdir, GitRepo};
+use crate::prompt::confirm;
+
+/// Scratch directory in the repository root used while a checkout is moved
+const STAGING_DIR: &str = ".gwik-convert";
+
+pub fn run(to_normal: bool, yes: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+
+    if to_normal {
+        convert_to_normal(&repo, &config, yes)
+    } else {
+        convert_to_bare(&repo, &config, yes)
+    }
+}
+
+/// Turn a normal clone into a bare repository whose checkout becomes the source worktree
+fn convert_to_bare(repo: &GitRepo, config: &Config, yes: bool) -> Result<()> {
+    if repo.is_bare {
+        bail!("Repository is already bare. Use --to-normal to convert it back");
+    }
+    if config.worktree_dir_in_git() {
+        bail!("worktree_dir is inside .git and would be lost. Change worktree_dir and run `gwik repair` first");
+    }
+    if config.source_worktree.is_some() {
+        bail!("gwik.toml already sets source_worktree. Remove it before converting");
+    }
+    if repo.root.join(".gitmodules").exists() {
+        bail!("Repositories with submodules cannot be converted safely");
+    }
+    let worktree_top = worktree_dir_name(config)?;
+
+    let main = repo
+        .main_worktree(None)?
+        .context("Main checkout not found")?;
+    let Some(branch) = main.branch.clone() else {
+        bail!("Main checkout is in detached HEAD state. Check out a branch first");
+    };
+    if main.operation_in_progress()? {
+        bail!("A rebase, merge or similar operation is in progress in the main checkout");
+    }
+
+    let source = GitRepo::branch_to_dirname(&branch);
+    if repo.git_dir.join(&source).exists() || source == worktree_top {
+        bail!(
+            "Source worktree name '{}' clashes with an entry in .git",
+            source
+        );
+    }
+    let staging = repo.root.join(STAGING_DIR);
+    if staging.exists() {
+        bail!("{} already exists", staging.display());
+    }
+
+    let linked: Vec<PathBuf> = repo
+        .list_worktrees()?
+        .into_iter()
+        .map(|wt| wt.path)
+        .collect();
+    let subdir = current_subdir(repo, &repo.root)?;
+
+    if !yes
+        && !confirm(&format!(
+            "Convert {} to a bare repository with source worktree '{}'?",
+            repo.root.display(),
+            source
+        ))?
+    {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    // Register the source worktree in the staging area with the main checkout's index
+    let staged_source = staging.join(&source);
+    repo.create_worktree_no_checkout(&staged_source, &branch)?;
+    let admin_dir = read_gitdir(&staged_source.join(".git"))
+        .context("Failed to locate the new worktree's git directory")?;
+    let index = repo.git_dir.join("index");
+    if index.exists() {
+        fs::copy(&index, admin_dir.join("index"))?;
+    }
+
+    // Move the checkout's files (tracked, modified and untracked alike)
+    if let Err(e) = move_entries(
+        &repo.root,
+        &staged_source,
+        &[".git", STAGING_DIR, &worktree_top],
+    ) {
+        let _ = fs::remove_dir_all(&staging);
+        let _ = fs::remove_dir_all(&admin_dir);
+        return Err(e);
+    }
+
+    // From here on the checkout lives in the staging area; put it back if anything fails
+    let source_path = repo.root.join(&source);
+    let bare = match finish_bare(repo, &staged_source, &source_path) {
+        Ok(bare) => bare,
+        Err(e) => {
+            if let Err(undo) = undo_bare(
+                repo,
+                &staged_source,
+                &source_path,
+                &admin_dir,
+                &worktree_top,
+            ) {
+                eprintln!("Error: Could not undo the conversion: {:#}", undo);
+                eprintln!("To recover the normal repository by hand:");
+                eprintln!(
+                    "  1. Move everything in {} except {} and {} into {}",
+                    repo.root.display(),
+                    worktree_top,
+                    STAGING_DIR,
+                    repo.git_dir.display()
+                );
+                eprintln!(
+                    "  2. Run `git config core.bare false` in {}",
+                    repo.root.display()
+                );
+                eprintln!(
+                    "  3. Delete {}/.git, then move your files from {} (or {}) back to {}",
+                    staged_source.display(),
+                    staged_source.display(),
+                    source_path.display(),
+                    repo.root.display()
+                );
+                eprintln!("  4. Run `git worktree prune`");
+            }
+            return Err(e);
+        }
+    };
+
+    let mut paths = vec![source_path.clone()];
+    paths.extend(linked);
+    bare.repair_worktrees(&paths)?;
+
+    set_source_worktree(&config::local_config_path(&bare.git_dir), Some(&source))?;
+
+    eprintln!(
+        "Converted {} to a bare repository with source worktree {}",
+        bare.root.display(),
+        source
+    );
+
+    if let Some(subdir) = subdir {
+        print_cd(&source_path, &subdir);
+    }
+
+    Ok(())
+}
+
+/// Turn a bare repository back into a normal clone checked out at its source worktree
+fn convert_to_normal(repo: &GitRepo, config: &Config, yes: bool) -> Result<()> {
+    if !repo.is_bare {
+        bail!("Repository is not bare");
+    }
+    let Some(source) = config.source_worktree.as_deref() else {
+        bail!("gwik.toml has no source_worktree, so there is no checkout to keep as the main one");
+    };
+    if Path::new(source).components().count() != 1 {
+        bail!("source_worktree must be a directory directly inside the repository");
+    }
+    let worktree_top = worktree_dir_name(config)?;
+
+    let source_path = repo.root.join(source);
+    let worktrees = repo.list_worktrees()?;
+    let Some(main) = worktrees.iter().find(|wt| wt.path == source_path) else {
+        bail!(
+            "source_worktree '{}' is not a worktree of this repository",
+            source
+        );
+    };
+    if main.branch.is_none() {
+        bail!("Source worktree is in detached HEAD state. Check out a branch first");
+    }
+    if main.operation_in_progress()? {
+        bail!("A rebase, merge or similar operation is in progress in the source worktree");
+    }
+
+    // Anything else inside the root would be swept into .git
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+    for wt in &worktrees {
+        if wt.path != source_path
+            && wt.path.starts_with(&repo.root)
+            && !wt.path.starts_with(&worktree_dir)
+        {
+            bail!(
+                "Worktree {} is inside the repository but outside {}. Move it with `gwik repair` first",
+                wt.path.display(),
+                config.worktree_dir
+            );
+        }
+    }
+
+    let staging = repo.root.join(STAGING_DIR);
+    for path in [
+        staging.clone(),
+        repo.root.join(".git"),
+        source_path.join(&worktree_top),
+        source_path.join(STAGING_DIR),
+    ] {
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+    }
+
+    let admin_id = read_gitdir(&source_path.join(".git"))
+        .and_then(|dir| dir.file_name().map(|n| n.to_os_string()))
+        .context("Failed to locate the source worktree's git directory")?;
+    let linked: Vec<PathBuf> = worktrees
+        .iter()
+        .filter(|wt| wt.path != source_path)
+        .map(|wt| wt.path.clone())
+        .collect();
+    let subdir = current_subdir(repo, &source_path)?;
+
+    if !yes
+        && !confirm(&format!(
+            "Convert {} to a normal repository checked out at '{}'?",
+            repo.root.display(),
+            source
+        ))?
+    {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    // Move the source checkout aside so its files cannot clash with git's
+    let staged_source = staging.join(source);
+    fs::create_dir(&staging)?;
+    fs::rename(&source_path, &staged_source)?;
+
+    // The repository root becomes .git
+    let git_dir = repo.root.join(".git");
+    fs::create_dir(&git_dir)?;
+    if let Err(e) = move_entries(&repo.root, &git_dir, &[".git", STAGING_DIR, &worktree_top]) {
+        let _ = fs::remove_dir(&git_dir);
+        let _ = fs::rename(&staged_source, &source_path);
+        let _ = fs::remove_dir(&staging);
+        return Err(e);
+    }
+    let normal = GitRepo {
+        root: repo.root.clone(),
+        git_dir: git_dir.clone(),
+        is_bare: false,
+    };
+    let admin_dir = git_dir.join("worktrees").join(admin_id);
+
+    // From here on the checkout lives in the staging area; put it back if anything fails
+    let bare_head = fs::read(git_dir.join("HEAD"))?;
+    let bare_index = fs::read(git_dir.join("index")).ok();
+    if let Err(e) = finish_normal(&normal, &admin_dir, &staged_source) {
+        if let Err(undo) = undo_normal(
+            repo,
+            &normal,
+            &bare_head,
+            bare_index.as_deref(),
+            &staged_source,
+            &source_path,
+        ) {
+            eprintln!("Error: Could not undo the conversion: {:#}", undo);
+            eprintln!("To recover the bare repository by hand:");
+            eprintln!(
+                "  1. Move your files from {} back to {}",
+                staged_source.display(),
+                source_path.display()
+            );
+            eprintln!(
+                "  2. Move everything in {} into {}",
+                git_dir.display(),
+                repo.root.display()
+            );
+            eprintln!(
+                "  3. Run `git config core.bare true` in {}",
+                repo.root.display()
+            );
+        }
+        return Err(e);
+    }
+
+    // The conversion is complete; leftovers are only worth a warning
+    let cleanup = fs::remove_dir_all(&admin_dir)
+        .and_then(|()| fs::remove_file(staged_source.join(".git")))
+        .and_then(|()| fs::remove_dir(&staged_source))
+        .and_then(|()| fs::remove_dir(&staging));
+    if let Err(e) = cleanup {
+        eprintln!(
+            "Warning: Failed to clean up after converting ({}). Delete {} and run `git worktree prune`",
+            e,
+            staging.display()
+        );
+    }
+
+    if !linked.is_empty() {
+        normal.repair_worktrees(&linked)?;
+    }
+
+    set_source_worktree(&config::local_config_path(&normal.git_dir), None)?;
+
+    eprintln!("Converted {} to a normal repository", normal.root.display());
+
+    if let Some(subdir) = subdir {
+        print_cd(&normal.root, &subdir);
+    }
+
+    Ok(())
+}
+
+/// Make the git directory the repository root and move the staged checkout into it
+fn finish_bare(repo: &GitRepo, staged_source: &Path, source_path: &Path) -> Result<GitRepo> {
+    move_entries(&repo.git_dir, &repo.root, &[])?;
+    fs::remove_dir(&repo.git_dir)?;
+    let bare = GitRepo {
+        root: repo.root.clone(),
+        git_dir: repo.root.clone(),
+        is_bare: true,
+    };
+    bare.config_set("core.bare", "true")?;
+
+    fs::rename(staged_source, source_path)?;
+    if let Some(staging) = staged_source.parent() {
+        fs::remove_dir(staging)?;
+    }
+    Ok(bare)
+}
+
+/// Put a half-finished conversion to bare back into the normal layout
+fn undo_bare(
+    repo: &GitRepo,
+    staged_source: &Path,
+    source_path: &Path,
+    admin_dir: &Path,
+    worktree_top: &str,
+) -> Result<()> {
+    let staging = repo.root.join(STAGING_DIR);
+    if !staged_source.exists() {
+        fs::create_dir_all(&staging)?;
+        fs::rename(source_path, staged_source)?;
+    }
+
+    // Only git's files, the worktree directory and the staging area are in the root now
+    if !repo.git_dir.join("HEAD").exists() {
+        fs::create_dir_all(&repo.git_dir)?;
+        move_entries(
+            &repo.root,
+            &repo.git_dir,
+            &[".git", STAGING_DIR, worktree_top],
+        )?;
+    }
+    repo.config_set("core.bare", "false")?;
+
+    fs::remove_file(staged_source.join(".git"))?;
+    move_entries(staged_source, &repo.root, &[])?;
+    fs::remove_dir_all(&staging)?;
+    fs::remove_dir_all(admin_dir)?;
+    Ok(())
+}
+
+/// Give the main checkout the source worktree's HEAD, index and files
+fn finish_normal(normal: &GitRepo, admin_dir: &Path, staged_source: &Path) -> Result<()> {
+    normal.config_set("core.bare", "false")?;
+    fs::copy(admin_dir.join("HEAD"), normal.git_dir.join("HEAD"))?;
+    if admin_dir.join("index").exists() {
+        fs::copy(admin_dir.join("index"), normal.git_dir.join("index"))?;
+    }
+    // The .git file stays behind; it points at the source worktree's admin dir
+    move_entries(staged_source, &normal.root, &[".git"])
+}
+
+/// Put a half-finished conversion to normal back into the bare layout
+fn undo_normal(
+    repo: &GitRepo,
+    normal: &GitRepo,
+    head: &[u8],
+    index: Option<&[u8]>,
+    staged_source: &Path,
+    source_path: &Path,
+) -> Result<()> {
+    fs::write(normal.git_dir.join("HEAD"), head)?;
+    match index {
+        Some(index) => fs::write(normal.git_dir.join("index"), index)?,
+        None => {
+            let _ = fs::remove_file(normal.git_dir.join("index"));
+        }
+    }
+    move_entries(&normal.git_dir, &repo.root, &[])?;
+    fs::remove_dir(&normal.git_dir)?;
+    repo.config_set("core.bare", "true")?;
+
+    fs::rename(staged_source, source_path)?;
+    if let Some(staging) = staged_source.parent() {
+        fs::remove_dir(staging)?;
+    }
+    Ok(())
+}
+
+/// The worktree_dir entry in the repository root (nested or outside paths are refused)
+fn worktree_dir_name(config: &Config) -> Result<String> {
+    if Path::new(&config.worktree_dir).components().count() != 1 {
+        bail!(
+            "Only a worktree_dir directly inside the repository is supported (found '{}')",
+            config.worktree_dir
+        );
+    }
+    Ok(config.worktree_dir.clone())
+}
+
+/// Subdirectory of `checkout` the user is in, if they are in it
+fn current_subdir(repo: &GitRepo, checkout: &Path) -> Result<Option<PathBuf>> {
+    if repo.current_checkout()?.as_deref() != Some(checkout) {
+        return Ok(None);
+    }
+
+    let cwd = std::env::current_dir()?.canonicalize()?;
+    Ok(cwd.strip_prefix(checkout).ok().map(Path::to_path_buf))
+}
+
+fn print_cd(checkout: &Path, subdir: &Path) {
+    let target = checkout.join(subdir);
+    if !subdir.as_os_str().is_empty() && target.is_dir() {
+        println!("cd {}", target.display());
+    } else {
+        println!("cd {}", checkout.display());
+    }
+}
+
+/// Set or remove `source_worktree` in gwik.toml, keeping everything else
+fn set_source_worktree(path: &Path, source: Option<&str>) -> Result<()> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let rest: Vec<&str> = content
+        .lines()
+        .filter(|line| {
+            line.trim_start()
+                .strip_prefix("source_worktree")
+                .is_none_or(|r| !r.trim_start().starts_with('='))
+        })
+        .collect();
+
+    // Top-level keys must come before any table, so prepend
+    let mut lines = Vec::new();
+    let entry = source.map(|s| format!("source_worktree = \"{}\"", s));
+    lines.extend(entry.as_deref());
+    lines.extend(rest);
+
+    if lines.iter().all(|l| l.trim().is_empty()) {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    fs::write(path, lines.join("\n") + "\n").context("Failed to write gwik.toml")
+}
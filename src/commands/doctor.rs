@@ -0,0 +1,351 @@
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Result};
+
+use crate::commands::repair;
+use crate::config::{self, Config};
+use crate::git::{GitRepo, WorktreeInfo};
+
+/// Oldest git that supports everything gwik runs (`worktree repair`, `--path-format`)
+const MIN_GIT_VERSION: (u32, u32) = (2, 31);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Ok,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Ok => write!(f, "ok"),
+            Level::Warn => write!(f, "warn"),
+            Level::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Collects check results and prints them as they come in
+#[derive(Default)]
+struct Report {
+    warnings: usize,
+    errors: usize,
+}
+
+impl Report {
+    fn ok(&mut self, message: impl fmt::Display) {
+        self.print(Level::Ok, message, None);
+    }
+
+    fn warn(&mut self, message: impl fmt::Display, fix: impl fmt::Display) {
+        self.warnings += 1;
+        self.print(Level::Warn, message, Some(fix.to_string()));
+    }
+
+    fn error(&mut self, message: impl fmt::Display, fix: impl fmt::Display) {
+        self.errors += 1;
+        self.print(Level::Error, message, Some(fix.to_string()));
+    }
+
+    fn print(&self, level: Level, message: impl fmt::Display, fix: Option<String>) {
+        println!("[{}] {}", level, message);
+        if let Some(fix) = fix {
+            println!("       fix: {}", fix);
+        }
+    }
+}
+
+pub fn run() -> Result<()> {
+    let mut report = Report::default();
+
+    check_git(&mut report);
+    check_shell_wrapper(&mut report);
+    check_global_config(&mut report);
+
+    match GitRepo::detect() {
+        Ok(repo) => check_repo(&mut report, &repo),
+        Err(_) => report.ok("Not inside a git repository (repository checks skipped)"),
+    }
+
+    if report.errors > 0 {
+        bail!(
+            "{} problem(s) found, {} warning(s)",
+            report.errors,
+            report.warnings
+        );
+    }
+    if report.warnings > 0 {
+        eprintln!("No problems found, {} warning(s)", report.warnings);
+    } else {
+        eprintln!("No problems found");
+    }
+
+    Ok(())
+}
+
+fn check_git(report: &mut Report) {
+    let output = match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        _ => {
+            report.error(
+                "git not found",
+                "Install git and make sure it is on your PATH",
+            );
+            return;
+        }
+    };
+
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match parse_git_version(&version) {
+        Some(v) if v >= MIN_GIT_VERSION => report.ok(&version),
+        Some(_) => report.warn(
+            format!(
+                "{} is older than {}.{}",
+                version, MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            ),
+            "Upgrade git; some commands (repair, status, sync) may fail",
+        ),
+        None => report.warn(
+            format!("Could not parse git version: {}", version),
+            "Check that `git` is the real git binary",
+        ),
+    }
+}
+
+/// Extract (major, minor) from `git --version` output
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.strip_prefix("git version ")?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_shell_wrapper(report: &mut Report) {
+    if std::env::var_os("GWIK_WRAPPER").is_some() {
+        report.ok("Shell wrapper is active");
+        return;
+    }
+
+    let shell = std::env::var("SHELL")
+        .ok()
+        .and_then(|s| {
+            Path::new(&s)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .filter(|s| s == "bash" || s == "zsh")
+        .unwrap_or_else(|| "bash".to_string());
+    report.warn(
+        "Shell wrapper is not active; gwik cannot change your directory",
+        format!("Add `eval \"$(gwik init {shell})\"` to your ~/.{shell}rc and restart the shell"),
+    );
+}
+
+fn check_global_config(report: &mut Report) {
+    let Some(path) = config::global_config_path() else {
+        report.warn(
+            "Could not determine the config directory",
+            "Set HOME or XDG_CONFIG_HOME",
+        );
+        return;
+    };
+
+    if path.exists() {
+        check_config_file(report, &path, config::check_global_config(&path));
+    } else {
+        report.ok(format!("No global config at {}", path.display()));
+    }
+
+    let Ok(config) = Config::load_global() else {
+        return;
+    };
+    for root in &config.roots {
+        if root.is_dir() {
+            report.ok(format!("Root {} exists", root.display()));
+        } else {
+            report.warn(
+                format!("Root {} does not exist", root.display()),
+                format!("Create it or remove it from `roots` in {}", path.display()),
+            );
+        }
+    }
+}
+
+fn check_config_file(report: &mut Report, path: &Path, result: Result<Vec<String>>) {
+    match result {
+        Ok(unknown) if unknown.is_empty() => {
+            report.ok(format!("Config {} parsed", path.display()));
+        }
+        Ok(unknown) => report.warn(
+            format!(
+                "Config {} has unknown keys: {}",
+                path.display(),
+                unknown.join(", ")
+            ),
+            "Check the spelling of these keys against the README; they are ignored",
+        ),
+        Err(e) => report.error(
+            format!("Config {} could not be parsed: {}", path.display(), e),
+            "Fix the syntax error; until then gwik ignores the whole file",
+        ),
+    }
+}
+
+fn check_repo(report: &mut Report, repo: &GitRepo) {
+    let local_path = config::local_config_path(&repo.git_dir);
+    if local_path.exists() {
+        check_config_file(report, &local_path, config::check_local_config(&local_path));
+    }
+
+    let config = match Config::load(&repo.git_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            report.error(format!("Failed to load config: {}", e), "Fix the config");
+            return;
+        }
+    };
+
+    let worktrees = match repo.list_all_worktrees() {
+        Ok(worktrees) => {
+            report.ok(format!(
+                "git worktree list works in {}",
+                repo.root.display()
+            ));
+            worktrees
+        }
+        Err(e) => {
+            report.error(
+                format!("git worktree list failed: {}", e.to_string().trim()),
+                "Upgrade git or check the repository with `git fsck`",
+            );
+            return;
+        }
+    };
+
+    // .gitignore keeps worktrees out of the main checkout's status
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+    if worktree_dir.is_dir() && !config.worktree_dir_in_git() && !repo.is_bare {
+        if worktree_dir.join(".gitignore").exists() {
+            report.ok(format!("{} has a .gitignore", worktree_dir.display()));
+        } else {
+            report.warn(
+                format!("{} has no .gitignore", worktree_dir.display()),
+                format!(
+                    "Run `echo '*' > {}`",
+                    worktree_dir.join(".gitignore").display()
+                ),
+            );
+        }
+    }
+
+    for wt in &worktrees {
+        if let Some(reason) = &wt.prunable {
+            report.warn(
+                format!("Worktree {} is prunable: {}", wt.path.display(), reason),
+                "Run `git worktree prune` if it was deleted, or `gwik repair` if it was moved",
+            );
+        }
+        if let Some(reason) = &wt.locked {
+            let reason = if reason.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", reason)
+            };
+            report.warn(
+                format!("Worktree {} is locked{}", wt.path.display(), reason),
+                format!(
+                    "Run `git worktree unlock {}` when no longer needed",
+                    wt.path.display()
+                ),
+            );
+        }
+    }
+
+    if repo.is_bare {
+        check_source_worktree(report, repo, &config, &worktrees);
+    }
+
+    match repair::diagnose(repo, &config) {
+        Ok(diagnosis) if diagnosis.is_healthy() => report.ok("Worktree links are healthy"),
+        Ok(diagnosis) => {
+            for path in &diagnosis.broken {
+                report.error(
+                    format!("Broken gitdir link: {}", path.display()),
+                    "Run `gwik repair`",
+                );
+            }
+            for path in &diagnosis.outside {
+                report.warn(
+                    format!(
+                        "Worktree outside {}: {}",
+                        config.worktree_dir,
+                        path.display()
+                    ),
+                    "Run `gwik repair` to move it",
+                );
+            }
+            // Missing worktrees are already reported as prunable
+        }
+        Err(e) => report.error(
+            format!("Failed to check worktree links: {}", e),
+            "Run `gwik repair`",
+        ),
+    }
+}
+
+fn check_source_worktree(
+    report: &mut Report,
+    repo: &GitRepo,
+    config: &Config,
+    worktrees: &[WorktreeInfo],
+) {
+    let Some(source) = &config.source_worktree else {
+        report.warn(
+            "Bare repository has no `source_worktree`",
+            format!(
+                "Add `source_worktree = \"<dir>\"` to {}",
+                config::local_config_path(&repo.git_dir).display()
+            ),
+        );
+        return;
+    };
+
+    let path = repo.root.join(source);
+    if worktrees.iter().any(|wt| wt.path == path) {
+        report.ok(format!("source_worktree {} is a worktree", source));
+    } else {
+        report.error(
+            format!(
+                "source_worktree {} is not a worktree of this repository",
+                source
+            ),
+            format!(
+                "Run `git worktree add {}` or fix `source_worktree` in gwik.toml",
+                source
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.39.5"), Some((2, 39)));
+        assert_eq!(
+            parse_git_version("git version 2.37.1 (Apple Git-137.1)"),
+            Some((2, 37))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.44.0.windows.1"),
+            Some((2, 44))
+        );
+        assert_eq!(parse_git_version("hub version 2.14"), None);
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::output::render_table;
+use crate::pattern::glob_match;
+use crate::scanner::scan_repos;
+
+/// A worktree to run the command in
+struct Job {
+    /// Worktree name, qualified with owner/repo for --all (`@` for the main checkout)
+    label: String,
+    path: PathBuf,
+}
+
+impl Job {
+    /// Unqualified worktree name
+    fn name(&self) -> &str {
+        self.label.rsplit('/').next().unwrap_or(&self.label)
+    }
+}
+
+/// Outcome of running the command in one worktree
+enum Outcome {
+    Exited(i32),
+    Signaled,
+    SpawnFailed(String),
+}
+
+impl Outcome {
+    fn success(&self) -> bool {
+        matches!(self, Outcome::Exited(0))
+    }
+}
+
+pub fn run(
+    all: bool,
+    include_main: bool,
+    parallel: usize,
+    filters: &[String],
+    cmd: &[String],
+) -> Result<()> {
+    let Some((program, args)) = cmd.split_first() else {
+        bail!("No command given. Usage: gwik exec -- <cmd>");
+    };
+
+    let jobs: Vec<Job> = collect_jobs(all, include_main)?
+        .into_iter()
+        .filter(|job| filters.is_empty() || filters.iter().any(|f| glob_match(f, job.name())))
+        .collect();
+
+    if jobs.is_empty() {
+        eprintln!("No worktrees to run in.");
+        return Ok(());
+    }
+
+    let width = jobs.iter().map(|j| j.label.len()).max().unwrap_or(0);
+    let queue = Mutex::new(jobs.iter().enumerate().collect::<VecDeque<_>>());
+    let outcomes: Mutex<Vec<Option<Outcome>>> = Mutex::new((0..jobs.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..parallel.clamp(1, jobs.len()) {
+            scope.spawn(|| loop {
+                let Some((index, job)) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+                let prefix = format!("[{:width$}]", job.label, width = width);
+                let outcome = run_one(&prefix, &job.path, program, args);
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+
+    let outcomes: Vec<Outcome> = outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+
+    // Summary table
+    eprintln!();
+    let rows: Vec<[String; 2]> = jobs
+        .iter()
+        .zip(&outcomes)
+        .map(|(job, outcome)| {
+            let status = match outcome {
+                Outcome::Exited(code) => code.to_string(),
+                Outcome::Signaled => "signal".to_string(),
+                Outcome::SpawnFailed(e) => format!("error: {}", e),
+            };
+            [job.label.clone(), status]
+        })
+        .collect();
+    for line in render_table(&["WORKTREE", "EXIT"], &rows) {
+        eprintln!("{}", line);
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.success()).count();
+    if failed > 0 {
+        bail!("Command failed in {} of {} worktrees", failed, jobs.len());
+    }
+
+    Ok(())
+}
+
+/// Worktrees of the current repository, or of all repositories with --all
+fn collect_jobs(all: bool, include_main: bool) -> Result<Vec<Job>> {
+    if all {
+        let config = Config::load_global()?;
+        let mut jobs = Vec::new();
+        for repo in scan_repos(&config.roots)? {
+            if let Some(main) = repo.main.as_ref().filter(|_| include_main) {
+                jobs.push(Job {
+                    label: format!("{}/@", repo.short_name()),
+                    path: main.path.clone(),
+                });
+            }
+            for wt in &repo.worktrees {
+                jobs.push(Job {
+                    label: format!("{}/{}", repo.short_name(), wt.name()),
+                    path: wt.path.clone(),
+                });
+            }
+        }
+        return Ok(jobs);
+    }
+
+    let repo = GitRepo::detect()?;
+    let mut jobs = Vec::new();
+    let mut worktrees = repo.list_worktrees()?;
+
+    if include_main {
+        let config = Config::load(&repo.git_dir)?;
+        if let Some(main) = repo.main_worktree(config.source_worktree.as_deref())? {
+            worktrees.retain(|wt| wt.path != main.path);
+            jobs.push(Job {
+                label: "@".to_string(),
+                path: main.path,
+            });
+        }
+    }
+
+    jobs.extend(worktrees.into_iter().map(|wt| Job {
+        label: wt.name(),
+        path: wt.path,
+    }));
+    Ok(jobs)
+}
+
+/// Run the command in a worktree, prefixing each output line
+fn run_one(prefix: &str, dir: &Path, program: &str, args: &[String]) -> Outcome {
+    let child = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("{} failed to run {}: {}", prefix, program, e);
+            return Outcome::SpawnFailed(e.to_string());
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    thread::scope(|scope| {
+        if let Some(out) = stdout {
+            scope.spawn(|| forward_lines(out, |line| println!("{} {}", prefix, line)));
+        }
+        if let Some(err) = stderr {
+            scope.spawn(|| forward_lines(err, |line| eprintln!("{} {}", prefix, line)));
+        }
+    });
+
+    match child.wait() {
+        Ok(status) => match status.code() {
+            Some(code) => Outcome::Exited(code),
+            None => Outcome::Signaled,
+        },
+        Err(e) => Outcome::SpawnFailed(e.to_string()),
+    }
+}
+
+/// Print each line of the output; invalid UTF-8 is replaced rather than ending the stream
+fn forward_lines(reader: impl Read, print: impl Fn(&str)) {
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.strip_suffix('\n').unwrap_or(&line);
+                print(line.strip_suffix('\r').unwrap_or(line));
+            }
+        }
+    }
+}
@@ -1,37 +1,179 @@
-use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
 
 use crate::config::Config;
-use crate::git::GitRepo;
-use crate::scanner::scan_all_worktrees;
+use crate::git::{GitRepo, WorktreeInfo};
+use crate::pattern::glob_match;
+use crate::scanner::{scan_repos, ScannedRepo};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Filters applied to individual worktrees
+#[derive(Debug, Default)]
+pub struct WorktreeFilter {
+    /// Glob pattern the branch name must match
+    pub branch: Option<String>,
+    /// Only worktrees with uncommitted changes or untracked files
+    pub dirty: bool,
+    /// Only worktrees whose last commit is older than this many days
+    pub stale_days: Option<u64>,
+}
+
+impl WorktreeFilter {
+    pub fn is_empty(&self) -> bool {
+        self.branch.is_none() && !self.dirty && self.stale_days.is_none()
+    }
+
+    pub fn matches(&self, wt: &WorktreeInfo) -> Result<bool> {
+        if let Some(pattern) = &self.branch {
+            match &wt.branch {
+                Some(branch) if glob_match(pattern, branch) => {}
+                _ => return Ok(false),
+            }
+        }
 
-pub fn run(all: bool) -> Result<()> {
+        if self.dirty && !wt.is_dirty().context("Failed to check for changes")? {
+            return Ok(false);
+        }
+
+        if let Some(days) = self.stale_days {
+            let time = wt
+                .last_commit_time()
+                .context("Failed to read the last commit time")?;
+            let Some(time) = time else {
+                return Ok(false);
+            };
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            if now.saturating_sub(time) < days.saturating_mul(SECONDS_PER_DAY) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Like `matches`, but reports worktrees that could not be checked and leaves them out
+    fn keeps(&self, wt: &WorktreeInfo) -> bool {
+        self.matches(wt).unwrap_or_else(|e| {
+            eprintln!("Warning: Skipping {}: {:#}", wt.path.display(), e);
+            false
+        })
+    }
+}
+
+pub fn run(
+    all: bool,
+    group: bool,
+    include_main: bool,
+    repo: Option<&str>,
+    filter: &WorktreeFilter,
+) -> Result<()> {
     if all {
-        run_all()
+        run_all(group, include_main, repo, filter)
     } else {
-        run_current_repo()
+        run_current_repo(include_main, filter)
     }
 }
 
-fn run_current_repo() -> Result<()> {
+fn run_current_repo(include_main: bool, filter: &WorktreeFilter) -> Result<()> {
     let repo = GitRepo::detect()?;
-    let worktrees = repo.list_worktrees()?;
+    let mut worktrees = repo.list_worktrees()?;
+
+    if include_main {
+        let config = Config::load(&repo.git_dir)?;
+        if let Some(main) = repo.main_worktree(config.source_worktree.as_deref())? {
+            // For bare repos the source worktree is also a linked worktree
+            worktrees.retain(|wt| wt.path != main.path);
+            worktrees.insert(0, main);
+        }
+    }
 
-    for wt in worktrees {
+    for wt in worktrees.iter().filter(|wt| filter.keeps(wt)) {
         println!("{}", wt.path.display());
     }
 
     Ok(())
 }
 
-fn run_all() -> Result<()> {
-    let repo = GitRepo::detect()?;
-    let config = Config::load(&repo.git_dir)?;
+fn run_all(
+    group: bool,
+    include_main: bool,
+    repo_pattern: Option<&str>,
+    filter: &WorktreeFilter,
+) -> Result<()> {
+    // Only the global config is needed, so this works outside of a repository
+    let config = Config::load_global()?;
+
+    let repos: Vec<ScannedRepo> = scan_repos(&config.roots)?
+        .into_iter()
+        .filter(|r| repo_pattern.is_none_or(|p| r.matches_pattern(p)))
+        .collect();
+
+    let mut first = true;
+    for repo in &repos {
+        let worktrees: Vec<&WorktreeInfo> = repo
+            .worktrees
+            .iter()
+            .filter(|wt| filter.keeps(wt))
+            .collect();
 
-    let worktrees = scan_all_worktrees(&config.roots)?;
+        if !group {
+            if include_main {
+                if let Some(main) = repo.main.as_ref().filter(|wt| filter.keeps(wt)) {
+                    println!("{}", main.path.display());
+                }
+            }
+            for wt in worktrees {
+                println!("{}", wt.path.display());
+            }
+            continue;
+        }
 
-    for path in worktrees {
-        println!("{}", path.display());
+        // The main checkout is always part of the tree, unless filters leave it out
+        let main = repo
+            .main
+            .as_ref()
+            .filter(|wt| filter.is_empty() || filter.keeps(wt));
+
+        // With worktree filters, skip repositories that have nothing to show
+        if worktrees.is_empty() && main.is_none() && !filter.is_empty() {
+            continue;
+        }
+
+        if !first {
+            println!();
+        }
+        first = false;
+
+        println!("{}", repo.name);
+        if let Some(main) = main {
+            let connector = if worktrees.is_empty() {
+                "└──"
+            } else {
+                "├──"
+            };
+            println!("{} {} (main)", connector, format_entry(main));
+        }
+        for (i, wt) in worktrees.iter().enumerate() {
+            let connector = if i + 1 == worktrees.len() {
+                "└──"
+            } else {
+                "├──"
+            };
+            println!("{} {}", connector, format_entry(wt));
+        }
     }
 
     Ok(())
 }
+
+fn format_entry(wt: &WorktreeInfo) -> String {
+    match &wt.branch {
+        Some(branch) => format!("{} [{}]", wt.path.display(), branch),
+        None => format!("{} [detached]", wt.path.display()),
+    }
+}
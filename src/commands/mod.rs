@@ -1,5 +1,16 @@
 pub mod cd;
+pub mod clone;
 pub mod close;
+pub mod complete;
+pub mod convert;
+pub mod doctor;
+pub mod exec;
 pub mod init;
 pub mod list;
+pub mod mv;
 pub mod open;
+pub mod repair;
+pub mod restore;
+pub mod status;
+pub mod sync;
+pub mod trash;
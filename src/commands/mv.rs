@@ -0,0 +1,99 @@
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::history;
+
+pub fn run(old: &str, new: &str, update_upstream: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+    let worktrees = repo.list_worktrees()?;
+
+    // Captured before the move, since the current directory may move with the worktree
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+    let old_path = worktree_dir.join(old);
+    // Git and current_dir() report resolved paths, while the worktree
+    // directory may be reached through a symlink
+    let old_canonical = old_path.canonicalize().unwrap_or(old_path.clone());
+
+    let Some(wt) = worktrees
+        .iter()
+        .find(|wt| wt.path == old_path || wt.path == old_canonical)
+    else {
+        eprintln!("Error: Worktree '{}' not found", old);
+        if !worktrees.is_empty() {
+            eprintln!("\nAvailable worktrees:");
+            for wt in &worktrees {
+                eprintln!("  {}", wt.name());
+            }
+        }
+        bail!("Worktree '{}' not found", old);
+    };
+
+    let Some(old_branch) = wt.branch.clone() else {
+        bail!("Worktree '{}' has a detached HEAD; nothing to rename", old);
+    };
+
+    let new_path = worktree_dir.join(GitRepo::branch_to_dirname(new));
+    if new_path.exists() {
+        bail!(
+            "Worktree '{}' already exists",
+            GitRepo::branch_to_dirname(new)
+        );
+    }
+    if old_branch != new && repo.branch_exists(new)? {
+        bail!("Branch '{}' already exists", new);
+    }
+
+    // Rename the branch first, then move the directory; undo the rename if the move fails
+    if old_branch != new {
+        repo.rename_branch(&old_branch, new)?;
+    }
+    if old_path != new_path {
+        if let Err(e) = repo.move_worktree(&old_path, &new_path) {
+            if old_branch != new {
+                repo.rename_branch(new, &old_branch)?;
+            }
+            return Err(e);
+        }
+    }
+
+    eprintln!(
+        "Renamed worktree: {} -> {} (branch {} -> {})",
+        old,
+        GitRepo::branch_to_dirname(new),
+        old_branch,
+        new
+    );
+
+    if update_upstream {
+        let merge_key = format!("branch.{}.merge", new);
+        match repo.config_get(&merge_key)? {
+            Some(_) => {
+                repo.config_set(&merge_key, &format!("refs/heads/{}", new))?;
+                let remote = repo
+                    .config_get(&format!("branch.{}.remote", new))?
+                    .unwrap_or_default();
+                eprintln!("Upstream set to {}/{}", remote, new);
+            }
+            None => eprintln!("Branch '{}' has no upstream; nothing to update", new),
+        }
+    }
+
+    // Visits may be recorded under either form of the path
+    history::rename_visits(&old_path, &new_path);
+    if old_canonical != old_path {
+        let new_canonical = new_path.canonicalize().unwrap_or(new_path.clone());
+        history::rename_visits(&old_canonical, &new_canonical);
+    }
+
+    // Follow the worktree if we were inside it
+    if let Ok(subdir) = cwd.strip_prefix(&old_canonical) {
+        println!("cd {}", new_path.join(subdir).display());
+    }
+
+    Ok(())
+}
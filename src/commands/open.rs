@@ -1,16 +1,65 @@
-use std::fs;
-use std::io::{self, Write};
-use std::process::Command;
+use std::path::Path;
+use std::time::Duration;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
-use crate::config::Config;
-use crate::git::GitRepo;
+use crate::cmds;
+use crate::config::{Config, FetchPolicy};
+use crate::files::ensure_worktree_dir;
+use crate::forge::{Forge, PullRequest};
+use crate::git::{split_remote_ref, GitRepo, WorktreeInfo};
+use crate::history;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::prompt::confirm;
 
-pub fn run(branch: &str, yes: bool) -> Result<()> {
+/// How uncommitted changes of the current checkout are carried into the new worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarryChanges {
+    /// Apply them in the new worktree and clean the source
+    Move,
+    /// Apply them in the new worktree and keep them in the source
+    Copy,
+}
+
+#[derive(Debug, Default)]
+pub struct OpenOptions {
+    /// Skip confirmation prompts
+    pub yes: bool,
+    pub carry: Option<CarryChanges>,
+    /// Carry untracked files too
+    pub include_untracked: bool,
+    /// Pull request number to check out
+    pub pr: Option<u64>,
+    /// Remote to fetch the pull request from (default: origin)
+    pub remote: Option<String>,
+    /// Overrides the configured fetch policy
+    pub fetch: Option<FetchPolicy>,
+    /// Push a new branch and set its upstream
+    pub push: bool,
+}
+
+pub fn run(branch: Option<&str>, opts: &OpenOptions) -> Result<()> {
     let repo = GitRepo::detect()?;
     let config = Config::load(&repo.git_dir)?;
 
+    // `pr/<n>` is shorthand for --pr <n>, unless such a branch exists
+    let shorthand = match branch.and_then(|b| Some((b, PullRequest::parse_shorthand(b)?))) {
+        Some((name, number)) if !repo.branch_exists(name)? => Some(number),
+        _ => None,
+    };
+    let pr = opts.pr.or(shorthand).map(|number| PullRequest {
+        number,
+        remote: opts.remote.clone().unwrap_or_else(|| "origin".to_string()),
+    });
+
+    let branch = match (&pr, branch) {
+        (Some(_), Some(branch)) if opts.pr.is_some() => branch.to_string(),
+        (Some(pr), _) => pr.branch_name(),
+        (None, Some(branch)) => branch.to_string(),
+        (None, None) => bail!("Specify a branch name or --pr <number>"),
+    };
+    let branch = branch.as_str();
+
     let dirname = GitRepo::branch_to_dirname(branch);
     let worktree_dir = repo.worktree_dir(&config.worktree_dir);
     let worktree_path = worktree_dir.join(&dirname);
@@ -20,86 +69,344 @@ pub fn run(branch: &str, yes: bool) -> Result<()> {
         bail!("Worktree '{}' already exists", dirname);
     }
 
-    // Ensure worktree directory exists
-    if !worktree_dir.exists() {
-        fs::create_dir_all(&worktree_dir)?;
+    // A failing pre-open hook aborts before anything is created
+    let src = repo.working_dir(config.source_worktree.as_deref());
+    let hook_context = HookContext {
+        src: &src,
+        dst: &worktree_path,
+        branch: Some(branch),
+    };
+    hooks::run(&repo, &config.hooks, HookEvent::PreOpen, &hook_context)?;
+
+    ensure_worktree_dir(&worktree_dir, &config)?;
+
+    // Pull requests are always fetched below
+    let fetched = match &pr {
+        Some(_) => false,
+        None => {
+            let policy = opts.fetch.unwrap_or(config.fetch);
+            fetch_for(&repo, branch, policy, config.fetch_timeout)?
+        }
+    };
+
+    let pr_head = match &pr {
+        Some(pr) => {
+            let forge = Forge::resolve(&repo, config.forge, &pr.remote)?;
+            Some(pr.fetch(&repo, forge, config.fetch_timeout)?)
+        }
+        None => None,
+    };
+
+    // Take the changes first; they are put back if anything below fails
+    let carried = match opts.carry {
+        Some(mode) => Some(CarriedChanges::take(
+            &repo,
+            mode,
+            opts.include_untracked,
+            branch,
+        )?),
+        None => None,
+    };
+    // After a fetch, new branches fork from the fresh remote default branch, not the local HEAD
+    let fresh_base = match fetched {
+        true => repo.remote_default_branch()?,
+        false => None,
+    };
+    let start = carried
+        .as_ref()
+        .and_then(|c| c.source.head.as_deref())
+        .or(fresh_base.as_deref());
+
+    let created = match &pr_head {
+        Some(head) => create_pr_worktree(&repo, branch, &worktree_path, head),
+        None => create_worktree(&repo, branch, &worktree_path, opts.yes, start),
+    };
+    if let Some(changes) = &carried {
+        match created {
+            Ok(Created::NewBranch | Created::ExistingBranch) => {
+                changes.apply(&repo, &worktree_path)?
+            }
+            _ => changes.restore()?,
+        }
+    }
+    let created = created?;
+    if created == Created::Declined {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    if created == Created::NewBranch && pr.is_none() {
+        set_up_upstream(&repo, &config, branch, opts.push)?;
+    }
+
+    if let Some(pr) = &pr {
+        pr.record(&repo, branch)?;
+        eprintln!(
+            "Checked out PR #{} from {} as {}",
+            pr.number, pr.remote, branch
+        );
+    }
+
+    // Run post-creation commands whose conditions hold in the new worktree
+    let cmds: Vec<_> = config
+        .cmds
+        .iter()
+        .filter(|cmd| cmd.when.matches(branch, &worktree_path))
+        .cloned()
+        .collect();
+    cmds::run_all(&repo, &cmds, &hook_context)?;
+    hooks::run(&repo, &config.hooks, HookEvent::PostOpen, &hook_context)?;
+
+    // Output cd command
+    history::record_visit(&repo.root, &worktree_path);
+    println!("cd {}", worktree_path.display());
+
+    Ok(())
+}
+
+/// Fetch before resolving `name`, according to the fetch policy
+/// Fetch failures only warn, so `open` keeps working offline
+/// Returns whether a fetch succeeded
+fn fetch_for(repo: &GitRepo, name: &str, policy: FetchPolicy, timeout: Duration) -> Result<bool> {
+    let needed = match policy {
+        FetchPolicy::Never => false,
+        FetchPolicy::Always => true,
+        FetchPolicy::Missing => {
+            !repo.branch_exists(name)? && matches!(repo.resolve_remote_branch(name), Ok(None))
+        }
+    };
+    let remotes = repo.remotes()?;
+    if !needed || remotes.is_empty() {
+        return Ok(false);
+    }
+
+    // For <remote>/<branch>, fetch only that branch; otherwise any remote may have it
+    let target = split_remote_ref(&format!("refs/remotes/{}", name), &remotes);
+    let result = match &target {
+        Some(rb) => repo.fetch_with_timeout(
+            Some(&rb.remote),
+            Some(&format!(
+                "+refs/heads/{}:refs/remotes/{}",
+                rb.branch,
+                rb.full_name()
+            )),
+            timeout,
+        ),
+        None => repo.fetch_with_timeout(None, None, timeout),
+    };
+
+    let what = target.map_or_else(|| "all remotes".to_string(), |rb| rb.full_name());
+    match result {
+        Ok(()) => {
+            eprintln!("Fetched {}", what);
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("Warning: {}. Continuing with local refs", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Configure the upstream of a new branch, or push it right away
+/// A failed push only warns; the worktree is already usable
+fn set_up_upstream(repo: &GitRepo, config: &Config, branch: &str, push: bool) -> Result<()> {
+    let push = push || config.push_new_branches;
+    let remote = match &config.upstream_remote {
+        Some(remote) => Some(remote.clone()),
+        None if push => repo.remotes()?.into_iter().find(|r| r == "origin"),
+        None => None,
+    };
+    let Some(remote) = remote else {
+        if push {
+            eprintln!("Warning: no remote to push to. Set `upstream_remote`");
+        }
+        return Ok(());
+    };
 
-        // Create .gitignore if not inside .git
-        if !config.worktree_dir_in_git() {
-            let gitignore_path = worktree_dir.join(".gitignore");
-            fs::write(&gitignore_path, "*\n")?;
+    if push {
+        match repo.push_branch(&remote, branch) {
+            Ok(()) => eprintln!("Pushed {} to {}", branch, remote),
+            Err(e) => eprintln!("Warning: {}", e),
         }
+    } else {
+        repo.set_upstream(branch, &remote)?;
+        eprintln!("Set upstream to {}/{}", remote, branch);
     }
+    Ok(())
+}
 
-    // Check if it's a remote branch (e.g., origin/feature-x)
-    if let Some(remote_branch) = repo.remote_branch_exists(branch)? {
-        // Extract local branch name from remote (origin/feature-x -> feature-x)
-        let local_branch = remote_branch
-            .split('/')
-            .skip(1)
-            .collect::<Vec<_>>()
-            .join("/");
+/// How `create_worktree` went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Created {
+    /// A new branch was created (tracking branches count as existing)
+    NewBranch,
+    ExistingBranch,
+    /// The user declined to use an existing branch
+    Declined,
+}
 
-        repo.create_worktree_tracking(&worktree_path, &local_branch, &remote_branch)?;
+/// Create the worktree for a remote, existing or new branch
+/// New branches start at `start` if given
+fn create_worktree(
+    repo: &GitRepo,
+    branch: &str,
+    worktree_path: &Path,
+    yes: bool,
+    start: Option<&str>,
+) -> Result<Created> {
+    // Check if it's a remote branch (origin/feature-x, or feature-x found on a remote)
+    if let Some(remote_branch) = repo.resolve_remote_branch(branch)? {
+        let remote_name = remote_branch.full_name();
+        repo.create_worktree_tracking(worktree_path, &remote_branch.branch, &remote_name)?;
         eprintln!(
             "Created worktree at {} tracking {}",
             worktree_path.display(),
-            remote_branch
+            remote_name
         );
     } else if repo.branch_exists(branch)? {
         // Branch exists locally
-        if !yes {
-            eprint!(
-                "Branch '{}' already exists. Use existing branch? [y/N] ",
-                branch
-            );
-            io::stderr().flush()?;
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let input = input.trim().to_lowercase();
-
-            if input != "y" && input != "yes" {
-                eprintln!("Aborted.");
-                return Ok(());
-            }
+        let question = format!("Branch '{}' already exists. Use existing branch?", branch);
+        if !yes && !confirm(&question)? {
+            return Ok(Created::Declined);
         }
 
-        repo.create_worktree_existing_branch(&worktree_path, branch)?;
+        repo.create_worktree_existing_branch(worktree_path, branch)?;
         eprintln!("Created worktree at {}", worktree_path.display());
     } else {
         // Create new branch
-        repo.create_worktree(&worktree_path, branch)?;
+        match start {
+            Some(start) => repo.create_worktree_at(worktree_path, branch, start)?,
+            None => repo.create_worktree(worktree_path, branch)?,
+        }
         eprintln!("Created worktree at {}", worktree_path.display());
+        return Ok(Created::NewBranch);
     }
 
-    // Run post-creation commands
-    if !config.cmds.is_empty() {
-        let src = repo.working_dir(config.source_worktree.as_deref());
-        run_post_commands(&config.cmds, &src, &worktree_path)?;
+    Ok(Created::ExistingBranch)
+}
+
+/// Create the worktree for a pull request at its fetched head
+/// An existing branch is fast-forwarded, so it never starts out stale
+fn create_pr_worktree(
+    repo: &GitRepo,
+    branch: &str,
+    worktree_path: &Path,
+    head: &str,
+) -> Result<Created> {
+    let Some(tip) = repo.branch_tip(branch)? else {
+        repo.create_worktree_at(worktree_path, branch, head)?;
+        eprintln!("Created worktree at {}", worktree_path.display());
+        return Ok(Created::NewBranch);
+    };
+
+    if tip != head {
+        if !repo.is_ancestor(&tip, head)? {
+            bail!(
+                "Branch '{}' has commits that are not in the pull request. Delete it or pass another branch name",
+                branch
+            );
+        }
+        if let Some(wt) = repo
+            .list_all_worktrees()?
+            .into_iter()
+            .find(|wt| wt.branch.as_deref() == Some(branch))
+        {
+            bail!(
+                "Branch '{}' is already checked out at {}",
+                branch,
+                wt.path.display()
+            );
+        }
+        repo.update_ref(&format!("refs/heads/{}", branch), head)?;
+        eprintln!("Fast-forwarded {} to the pull request", branch);
     }
 
-    // Output cd command
-    println!("cd {}", worktree_path.display());
+    repo.create_worktree_existing_branch(worktree_path, branch)?;
+    eprintln!("Created worktree at {}", worktree_path.display());
+    Ok(Created::ExistingBranch)
+}
 
-    Ok(())
+/// Uncommitted changes taken from the current checkout, held as a stash commit
+struct CarriedChanges {
+    source: WorktreeInfo,
+    stash: String,
+    mode: CarryChanges,
 }
 
-fn run_post_commands(cmds: &[String], src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
-    let src_str = src.to_string_lossy();
-    let dst_str = dst.to_string_lossy();
+impl CarriedChanges {
+    fn take(
+        repo: &GitRepo,
+        mode: CarryChanges,
+        include_untracked: bool,
+        branch: &str,
+    ) -> Result<Self> {
+        let checkout = repo
+            .current_checkout()?
+            .context("Run gwik open from the checkout whose changes should be carried over")?;
+        let source = repo
+            .list_all_worktrees()?
+            .into_iter()
+            .find(|wt| wt.path == checkout)
+            .context("Current checkout is not a worktree of this repository")?;
 
-    for cmd in cmds {
-        let expanded = cmd.replace("$SRC", &src_str).replace("$DST", &dst_str);
+        let message = format!("gwik: changes for {}", branch);
+        let Some(stash) = source.stash_push(include_untracked, &message)? else {
+            if include_untracked {
+                bail!("No local changes to carry over");
+            }
+            bail!("No local changes to carry over (use --include-untracked for untracked files)");
+        };
 
-        eprintln!("Running: {}", expanded);
+        // Copying leaves the source as it was
+        if mode == CarryChanges::Copy {
+            source.stash_apply(&stash)?;
+        }
+
+        Ok(Self {
+            source,
+            stash,
+            mode,
+        })
+    }
 
-        let status = Command::new("sh").arg("-c").arg(&expanded).status()?;
+    /// Apply the changes in the new worktree, restoring the source on failure
+    fn apply(&self, repo: &GitRepo, worktree_path: &Path) -> Result<()> {
+        let target = repo
+            .list_all_worktrees()?
+            .into_iter()
+            .find(|wt| wt.path == worktree_path)
+            .context("New worktree not found")?;
 
-        if !status.success() {
-            bail!("Post-creation command failed: {}", expanded);
+        if let Err(e) = target.stash_apply(&self.stash) {
+            let _ = target.discard_changes();
+            self.restore()?;
+            return Err(e.context(format!(
+                "Failed to apply changes in {}; they were restored to {}",
+                worktree_path.display(),
+                self.source.path.display()
+            )));
         }
+
+        self.source.stash_drop(&self.stash)?;
+        let verb = match self.mode {
+            CarryChanges::Move => "Moved",
+            CarryChanges::Copy => "Copied",
+        };
+        eprintln!("{} changes from {}", verb, self.source.path.display());
+        Ok(())
     }
 
-    Ok(())
+    /// Put the changes back into the source checkout
+    fn restore(&self) -> Result<()> {
+        if self.mode == CarryChanges::Move {
+            self.source.stash_apply(&self.stash).with_context(|| {
+                format!(
+                    "Failed to restore changes; recover them with `git stash apply {}`",
+                    self.stash
+                )
+            })?;
+        }
+        self.source.stash_drop(&self.stash)
+    }
 }
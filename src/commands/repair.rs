@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::files::ensure_worktree_dir;
+use crate::git::{read_gitdir, GitRepo};
+use crate::prompt::confirm;
+
+/// Problems with the links between a repository and its worktrees
+#[derive(Debug, Default)]
+pub struct Diagnosis {
+    /// Worktree directories whose gitdir links are broken (fixable with `git worktree repair`)
+    pub broken: Vec<PathBuf>,
+    /// Registered worktrees whose directory no longer exists and was not found
+    pub missing: Vec<PathBuf>,
+    /// Valid worktrees living outside the configured worktree_dir
+    pub outside: Vec<PathBuf>,
+}
+
+impl Diagnosis {
+    pub fn is_healthy(&self) -> bool {
+        self.broken.is_empty() && self.missing.is_empty() && self.outside.is_empty()
+    }
+}
+
+/// Inspect gitdir links in both directions and worktree locations
+pub fn diagnose(repo: &GitRepo, config: &Config) -> Result<Diagnosis> {
+    let mut diagnosis = Diagnosis::default();
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+
+    // Worktree directories that gwik would manage
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Ok(entries) = fs::read_dir(&worktree_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.join(".git").is_file() {
+                candidates.push(path);
+            }
+        }
+    }
+    if repo.is_bare {
+        if let Some(source) = &config.source_worktree {
+            let path = repo.root.join(source);
+            if path.join(".git").is_file() {
+                candidates.push(path);
+            }
+        }
+    }
+
+    for path in &candidates {
+        if !link_is_valid(path) {
+            diagnosis.broken.push(path.clone());
+        }
+    }
+
+    // Registered worktrees whose recorded directory is gone
+    let names: Vec<_> = diagnosis
+        .broken
+        .iter()
+        .filter_map(|p| p.file_name())
+        .collect();
+    for wt in repo.list_worktrees()? {
+        if wt.path.exists() {
+            let in_worktree_dir = wt.path.parent() == Some(worktree_dir.as_path());
+            let is_source = repo.is_bare
+                && config
+                    .source_worktree
+                    .as_ref()
+                    .is_some_and(|s| repo.root.join(s) == wt.path);
+            if !in_worktree_dir && !is_source {
+                diagnosis.outside.push(wt.path);
+            }
+        } else if !wt.path.file_name().is_some_and(|n| names.contains(&n)) {
+            diagnosis.missing.push(wt.path);
+        }
+    }
+
+    Ok(diagnosis)
+}
+
+/// Check that a worktree's `.git` file points to an admin directory that points back
+fn link_is_valid(worktree: &Path) -> bool {
+    let Some(admin_dir) = read_gitdir(&worktree.join(".git")) else {
+        return false;
+    };
+    let Some(back) = read_gitdir(&admin_dir.join("gitdir")) else {
+        return false;
+    };
+
+    let expected = worktree.join(".git");
+    match (back.canonicalize(), expected.canonicalize()) {
+        (Ok(back), Ok(expected)) => back == expected,
+        _ => false,
+    }
+}
+
+pub fn run(check: bool, yes: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+    let diagnosis = diagnose(&repo, &config)?;
+
+    if diagnosis.is_healthy() {
+        eprintln!("All worktrees are healthy.");
+        return Ok(());
+    }
+
+    for path in &diagnosis.broken {
+        eprintln!("Broken gitdir link: {}", path.display());
+    }
+    for path in &diagnosis.missing {
+        eprintln!(
+            "Missing worktree: {} (run `git worktree prune` if it was deleted)",
+            path.display()
+        );
+    }
+    for path in &diagnosis.outside {
+        eprintln!("Outside {}: {}", config.worktree_dir, path.display());
+    }
+
+    if check {
+        anyhow::bail!("Worktree problems found. Run `gwik repair` to fix them");
+    }
+
+    if !diagnosis.broken.is_empty() {
+        repo.repair_worktrees(&diagnosis.broken)?;
+        for path in &diagnosis.broken {
+            eprintln!("Repaired: {}", path.display());
+        }
+    }
+
+    // Offer to migrate worktrees into the configured worktree_dir
+    let worktree_dir = repo.worktree_dir(&config.worktree_dir);
+    for path in &diagnosis.outside {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = worktree_dir.join(name);
+        if target.exists() {
+            eprintln!(
+                "Skipped {}: {} already exists",
+                path.display(),
+                target.display()
+            );
+            continue;
+        }
+
+        let question = format!("Move {} into {}?", path.display(), worktree_dir.display());
+        if !yes && !confirm(&question)? {
+            continue;
+        }
+
+        ensure_worktree_dir(&worktree_dir, &config)?;
+        repo.move_worktree(path, &target)?;
+        eprintln!("Moved: {} -> {}", path.display(), target.display());
+    }
+
+    Ok(())
+}
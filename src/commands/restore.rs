@@ -0,0 +1,44 @@
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::files::ensure_worktree_dir;
+use crate::git::GitRepo;
+use crate::history;
+use crate::trash;
+
+pub fn run(name: &str) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+    let entries = trash::list(&repo)?;
+
+    let Some(entry) = trash::find(&entries, name) else {
+        eprintln!("Error: No trashed worktree '{}'", name);
+        if !entries.is_empty() {
+            eprintln!("\nTrashed worktrees:");
+            for entry in &entries {
+                eprintln!("  {}", entry.meta.name);
+            }
+        }
+        bail!("No trashed worktree '{}'", name);
+    };
+
+    let path = &entry.meta.path;
+    if let Some(parent) = path.parent() {
+        ensure_worktree_dir(parent, &config)?;
+    }
+    let branch = trash::restore(&repo, entry)?;
+
+    match &branch {
+        Some(branch) => eprintln!("Restored worktree {} on {}", entry.meta.name, branch),
+        None => eprintln!(
+            "Restored worktree {} at {}",
+            entry.meta.name,
+            trash::short(&entry.meta.head)
+        ),
+    }
+
+    history::record_visit(&repo.root, path);
+    println!("cd {}", path.display());
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::git::{GitRepo, WorktreeInfo, WorktreeStatus};
+use crate::output::{column_widths, format_age};
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const DIM: &str = "\x1b[2m";
+
+/// One row of `gwik status`
+#[derive(Debug, Serialize)]
+struct StatusEntry {
+    /// Worktree name (`@` for the main checkout)
+    name: String,
+    path: PathBuf,
+    branch: Option<String>,
+    head: Option<String>,
+    is_main: bool,
+    #[serde(flatten)]
+    status: Option<WorktreeStatus>,
+    /// Why the status could not be read (`missing` when the directory is gone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub fn run(json: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+
+    let main = repo.main_worktree(config.source_worktree.as_deref())?;
+    let mut worktrees: Vec<(bool, WorktreeInfo)> = Vec::new();
+    if let Some(main) = &main {
+        worktrees.push((true, main.clone()));
+    }
+    worktrees.extend(
+        repo.list_worktrees()?
+            .into_iter()
+            .filter(|wt| main.as_ref().is_none_or(|m| m.path != wt.path))
+            .map(|wt| (false, wt)),
+    );
+
+    let base = repo.default_base(config.source_worktree.as_deref())?;
+    let stashes = repo.stash_counts()?;
+
+    // Query each worktree concurrently; deleted directories are reported, not queried
+    let statuses: Vec<Option<Result<WorktreeStatus>>> = thread::scope(|scope| {
+        let handles: Vec<_> = worktrees
+            .iter()
+            .map(|(_, wt)| scope.spawn(|| wt.path.exists().then(|| wt.status(base.as_deref()))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("status thread panicked"))
+            .collect()
+    });
+
+    let mut entries = Vec::new();
+    for ((is_main, wt), status) in worktrees.into_iter().zip(statuses) {
+        let (status, error) = match status {
+            Some(Ok(mut status)) => {
+                if let Some(branch) = &wt.branch {
+                    status.stashes = stashes.get(branch).copied().unwrap_or(0);
+                }
+                (Some(status), None)
+            }
+            Some(Err(e)) => (None, Some(format!("error: {:#}", e))),
+            None => (None, Some("missing".to_string())),
+        };
+        entries.push(StatusEntry {
+            name: if is_main { "@".to_string() } else { wt.name() },
+            path: wt.path,
+            branch: wt.branch,
+            head: wt.head,
+            is_main,
+            status,
+            error,
+        });
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        print_table(&entries, color);
+    }
+
+    Ok(())
+}
+
+fn print_table(entries: &[StatusEntry], color: bool) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let header = [
+        "WORKTREE",
+        "BRANCH",
+        "UPSTREAM",
+        "AHEAD",
+        "BEHIND",
+        "STAGED",
+        "UNSTAGED",
+        "UNTRACKED",
+        "STASH",
+        "LAST COMMIT",
+        "MERGED",
+    ];
+
+    // Each cell is (text, color); padding is computed on the plain text
+    let rows: Vec<Vec<(String, &str)>> = entries
+        .iter()
+        .map(|e| {
+            let name = (e.name.clone(), "");
+            let branch = (
+                e.branch.clone().unwrap_or_else(|| "(detached)".to_string()),
+                CYAN,
+            );
+            // Rows without a status show their error in place of the remaining columns
+            let Some(s) = &e.status else {
+                let error = e.error.clone().unwrap_or_default();
+                return vec![name, branch, (error, RED)];
+            };
+            let count = |n: usize, c: &'static str| {
+                if n == 0 {
+                    ("-".to_string(), DIM)
+                } else {
+                    (n.to_string(), c)
+                }
+            };
+            vec![
+                name,
+                branch,
+                match &s.upstream {
+                    Some(upstream) if s.upstream_gone => (format!("{} (gone)", upstream), RED),
+                    Some(upstream) => (upstream.clone(), ""),
+                    None => ("-".to_string(), DIM),
+                },
+                count(s.ahead.unwrap_or(0) as usize, GREEN),
+                count(s.behind.unwrap_or(0) as usize, RED),
+                count(s.staged, GREEN),
+                count(s.unstaged, YELLOW),
+                count(s.untracked, YELLOW),
+                count(s.stashes, YELLOW),
+                (
+                    s.last_commit_time
+                        .map(|t| format_age(now.saturating_sub(t)))
+                        .unwrap_or_else(|| "-".to_string()),
+                    "",
+                ),
+                match s.merged {
+                    Some(true) => ("yes".to_string(), GREEN),
+                    Some(false) => ("no".to_string(), ""),
+                    None => ("-".to_string(), DIM),
+                },
+            ]
+        })
+        .collect();
+
+    // Error messages span several columns, so they do not count toward the widths
+    let texts: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|r| {
+            let mut cells: Vec<&str> = r.iter().map(|(text, _)| text.as_str()).collect();
+            if cells.len() < header.len() {
+                cells.pop();
+                cells.resize(header.len(), "");
+            }
+            cells
+        })
+        .collect();
+    let widths = column_widths(&header, &texts);
+
+    let header_line: Vec<String> = header
+        .iter()
+        .zip(&widths)
+        .map(|(h, w)| format!("{:w$}", h, w = w))
+        .collect();
+    println!("{}", header_line.join("  ").trim_end());
+
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|((text, c), w)| {
+                let padded = format!("{:w$}", text, w = w);
+                if color && !c.is_empty() {
+                    format!("{}{}{}", c, padded, RESET)
+                } else {
+                    padded
+                }
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::forge::{Forge, PullRequest};
+use crate::git::{GitRepo, WorktreeInfo};
+
+/// How branches are brought up to date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    Rebase,
+    Merge,
+}
+
+pub fn run(mode: SyncMode, onto: Option<&str>, dry_run: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+
+    // Fetch once up front so every worktree sees the same base. A dry run
+    // compares against the refs from the last fetch, since fetching prunes
+    if dry_run {
+        eprintln!("Dry run: not fetching, using the last fetched remote state");
+    } else if repo.fetch_all()? {
+        eprintln!("Fetched all remotes");
+    }
+
+    let onto = match onto {
+        Some(onto) => onto.to_string(),
+        None => match repo.default_base(config.source_worktree.as_deref())? {
+            Some(base) => base,
+            None => bail!("Cannot determine the base branch. Use --onto <ref>"),
+        },
+    };
+
+    let main = repo.main_worktree(config.source_worktree.as_deref())?;
+    let worktrees: Vec<_> = repo
+        .list_worktrees()?
+        .into_iter()
+        .filter(|wt| main.as_ref().is_none_or(|m| m.path != wt.path))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    let mut failures = Vec::new();
+    for wt in &worktrees {
+        let name = wt.name();
+        // One broken worktree must not stop the others from syncing
+        match sync_one(&repo, &config, wt, mode, &onto, dry_run) {
+            Ok(Synced::Done) => {}
+            Ok(Synced::Conflict) => conflicts.push(name),
+            Err(e) => {
+                eprintln!("{}: ERROR - {:#}", name, e);
+                failures.push(name);
+            }
+        }
+    }
+
+    if !conflicts.is_empty() || !failures.is_empty() {
+        let mut problems = Vec::new();
+        if !conflicts.is_empty() {
+            problems.push(format!(
+                "{} worktree(s) have conflicts: {}",
+                conflicts.len(),
+                conflicts.join(", ")
+            ));
+        }
+        if !failures.is_empty() {
+            problems.push(format!(
+                "{} worktree(s) failed: {}",
+                failures.len(),
+                failures.join(", ")
+            ));
+        }
+        bail!(problems.join("; "));
+    }
+
+    Ok(())
+}
+
+enum Synced {
+    Done,
+    Conflict,
+}
+
+/// Bring one worktree up to date, reporting what happened on stderr
+fn sync_one(
+    repo: &GitRepo,
+    config: &Config,
+    wt: &WorktreeInfo,
+    mode: SyncMode,
+    onto: &str,
+    dry_run: bool,
+) -> Result<Synced> {
+    let name = wt.name();
+    let (verb, preposition) = match mode {
+        SyncMode::Rebase => ("rebase", "onto"),
+        SyncMode::Merge => ("merge", "from"),
+    };
+
+    let Some(branch) = &wt.branch else {
+        eprintln!("{}: skipped (detached HEAD)", name);
+        return Ok(Synced::Done);
+    };
+    if onto == branch || onto.ends_with(&format!("/{}", branch)) {
+        eprintln!("{}: skipped (base branch)", name);
+        return Ok(Synced::Done);
+    }
+    if wt.operation_in_progress()? {
+        eprintln!("{}: skipped (operation in progress)", name);
+        return Ok(Synced::Done);
+    }
+    if wt.is_dirty()? {
+        eprintln!("{}: skipped (uncommitted changes)", name);
+        return Ok(Synced::Done);
+    }
+
+    // Pull request branches follow the pull request, not the base
+    if let Some(pr) = PullRequest::of_branch(repo, branch)? {
+        // The pull request head is only known after fetching it
+        if dry_run {
+            eprintln!(
+                "{}: would fetch PR #{} and fast-forward {} to it",
+                name, pr.number, branch
+            );
+            return Ok(Synced::Done);
+        }
+
+        let forge = Forge::resolve(repo, config.forge, &pr.remote)?;
+        let head = pr.fetch(repo, forge, config.fetch_timeout)?;
+        let behind = wt.count_commits("HEAD", &head)?;
+        if behind == 0 {
+            eprintln!("{}: up to date with PR #{}", name, pr.number);
+        } else if wt.count_commits(&head, "HEAD")? > 0 {
+            eprintln!(
+                "{}: skipped (PR #{} was rewritten or has local commits)",
+                name, pr.number
+            );
+        } else {
+            wt.fast_forward(&head)?;
+            eprintln!("{}: fast-forwarded {} to PR #{}", name, branch, pr.number);
+        }
+        return Ok(Synced::Done);
+    }
+
+    let behind = wt.count_commits("HEAD", onto)?;
+    if behind == 0 {
+        eprintln!("{}: up to date", name);
+        return Ok(Synced::Done);
+    }
+
+    if dry_run {
+        eprintln!(
+            "{}: would {} {} {} {} ({} new commits)",
+            name, verb, branch, preposition, onto, behind
+        );
+        return Ok(Synced::Done);
+    }
+
+    let clean = match mode {
+        SyncMode::Rebase => wt.rebase(onto)?,
+        SyncMode::Merge => wt.merge(onto)?,
+    };
+
+    if clean {
+        eprintln!("{}: {}d {} {} {}", name, verb, branch, preposition, onto);
+        return Ok(Synced::Done);
+    }
+
+    eprintln!(
+        "{}: CONFLICT - {} left in progress in {}",
+        name,
+        verb,
+        wt.path.display()
+    );
+    eprintln!(
+        "{}:   resolve and run `git {} --continue`, or `git {} --abort`",
+        name, verb, verb
+    );
+    Ok(Synced::Conflict)
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::output::{format_age, render_table};
+use crate::trash;
+
+pub fn list() -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let entries = trash::list(&repo)?;
+
+    if entries.is_empty() {
+        eprintln!("Trash is empty.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|e| {
+            [
+                e.meta.name.clone(),
+                e.meta.branch.clone().unwrap_or_else(|| "-".to_string()),
+                format_age(e.age()),
+                e.id(),
+            ]
+        })
+        .collect();
+    for line in render_table(&["NAME", "BRANCH", "TRASHED", "ID"], &rows) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// Delete trashed worktrees older than trash_expire, or all of them
+pub fn empty(all: bool) -> Result<()> {
+    let repo = GitRepo::detect()?;
+    let config = Config::load(&repo.git_dir)?;
+    // --all needs no expiry, so a bad trash_expire cannot block it
+    let expire = if all {
+        0
+    } else {
+        config.trash_expire()?.as_secs()
+    };
+
+    let mut deleted = 0;
+    for entry in trash::list(&repo)? {
+        if all || entry.age() >= expire {
+            entry.delete(&repo)?;
+            deleted += 1;
+        }
+    }
+
+    eprintln!("Deleted {} trashed worktree(s)", deleted);
+    Ok(())
+}
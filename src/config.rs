@@ -1,47 +1,137 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
+use crate::cmds::{Cmd, CmdEntry};
+use crate::forge::Forge;
+use crate::hooks::Hooks;
+
 const DEFAULT_WORKTREE_DIR: &str = ".worktrees";
 
+/// How long `gwik open` waits for a fetch before continuing offline
+const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long `gwik trash empty` keeps trashed worktrees
+const DEFAULT_TRASH_EXPIRE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Reserved worktree name that always refers to the main checkout
+const MAIN_ALIAS: &str = "@";
+
+/// When `gwik open` fetches before resolving the branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchPolicy {
+    #[default]
+    Never,
+    /// Only when the branch is not found locally or on a remote
+    Missing,
+    Always,
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct GlobalConfig {
     pub worktree_dir: Option<String>,
+    pub main_alias: Option<String>,
+    pub keep_subdir: Option<bool>,
+    pub forge: Option<Forge>,
+    pub fetch: Option<FetchPolicy>,
+    pub fetch_timeout: Option<String>,
+    pub push_new_branches: Option<bool>,
+    pub upstream_remote: Option<String>,
+    pub trash_expire: Option<String>,
     #[serde(default)]
     pub roots: Vec<String>,
+    /// Keys gwik does not know about (reported by `gwik doctor`)
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, toml::Value>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 pub struct LocalConfig {
     pub worktree_dir: Option<String>,
+    pub main_alias: Option<String>,
+    pub keep_subdir: Option<bool>,
+    pub forge: Option<Forge>,
+    pub fetch: Option<FetchPolicy>,
+    pub fetch_timeout: Option<String>,
+    pub push_new_branches: Option<bool>,
+    pub upstream_remote: Option<String>,
+    pub trash_expire: Option<String>,
     /// Source worktree path (relative to repo root) for bare repositories
     pub source_worktree: Option<String>,
     #[serde(default)]
-    pub cmds: Vec<String>,
+    pub cmds: Vec<CmdEntry>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// Keys gwik does not know about (reported by `gwik doctor`)
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Config {
     pub worktree_dir: String,
     /// Source worktree path (relative to repo root) for bare repositories
     pub source_worktree: Option<String>,
     pub roots: Vec<PathBuf>,
-    pub cmds: Vec<String>,
+    /// Commands to run after creating a worktree
+    pub cmds: Vec<Cmd>,
+    /// Commands to run on open and close
+    pub hooks: Hooks,
+    /// Additional name for the main checkout (besides `@`)
+    pub main_alias: Option<String>,
+    /// Keep the current subdirectory when switching worktrees with `gwik cd`
+    pub keep_subdir: bool,
+    /// Forge type for pull requests (detected from the remote URL when unset)
+    pub forge: Option<Forge>,
+    /// When `gwik open` fetches
+    pub fetch: FetchPolicy,
+    pub fetch_timeout: Duration,
+    /// Push new branches created by `gwik open`
+    pub push_new_branches: bool,
+    /// Remote that new branches track
+    pub upstream_remote: Option<String>,
+    /// Age after which `gwik trash empty` deletes trashed worktrees
+    /// (unparsed, so a bad value only affects that command)
+    pub trash_expire: Option<String>,
 }
 
 impl Config {
     /// Load and merge global and local configurations
     pub fn load(git_dir: &Path) -> Result<Self> {
-        let global = load_global_config();
-        let local = load_local_config(git_dir);
+        Self::merge(load_global_config(), load_local_config(git_dir))
+    }
+
+    /// Load only the global configuration (for use outside of a repository)
+    pub fn load_global() -> Result<Self> {
+        Self::merge(load_global_config(), LocalConfig::default())
+    }
 
+    fn merge(global: GlobalConfig, local: LocalConfig) -> Result<Self> {
         // Local takes precedence over global, defaults as fallback
         let worktree_dir = local
             .worktree_dir
             .or(global.worktree_dir)
             .unwrap_or_else(|| DEFAULT_WORKTREE_DIR.to_string());
+        let main_alias = local.main_alias.or(global.main_alias);
+        let keep_subdir = local.keep_subdir.or(global.keep_subdir).unwrap_or(true);
+        let forge = local.forge.or(global.forge);
+        let fetch = local.fetch.or(global.fetch).unwrap_or_default();
+        let fetch_timeout = duration_or_default(
+            "fetch_timeout",
+            local.fetch_timeout.or(global.fetch_timeout),
+            DEFAULT_FETCH_TIMEOUT,
+        );
+        let push_new_branches = local
+            .push_new_branches
+            .or(global.push_new_branches)
+            .unwrap_or(false);
+        let upstream_remote = local.upstream_remote.or(global.upstream_remote);
+        let trash_expire = local.trash_expire.or(global.trash_expire);
 
         // Expand ~ in root paths
         let roots = global
@@ -50,8 +140,9 @@ impl Config {
             .filter_map(|r| expand_tilde(&r))
             .collect();
 
-        // cmds and source_worktree are local only
-        let cmds = local.cmds;
+        // cmds, hooks and source_worktree are local only
+        let cmds = local.cmds.into_iter().map(Cmd::from).collect();
+        let hooks = local.hooks;
         let source_worktree = local.source_worktree;
 
         Ok(Config {
@@ -59,19 +150,60 @@ impl Config {
             source_worktree,
             roots,
             cmds,
+            hooks,
+            main_alias,
+            keep_subdir,
+            forge,
+            fetch,
+            fetch_timeout,
+            push_new_branches,
+            upstream_remote,
+            trash_expire,
         })
     }
 
+    /// Age after which `gwik trash empty` deletes trashed worktrees
+    pub fn trash_expire(&self) -> Result<Duration> {
+        match &self.trash_expire {
+            Some(expire) => parse_duration(expire).context("Invalid trash_expire"),
+            None => Ok(DEFAULT_TRASH_EXPIRE),
+        }
+    }
+
+    /// Check if a worktree name refers to the main checkout
+    pub fn is_main_alias(&self, name: &str) -> bool {
+        name == MAIN_ALIAS || self.main_alias.as_deref() == Some(name)
+    }
+
     /// Check if worktree_dir is inside .git (no .gitignore needed)
     pub fn worktree_dir_in_git(&self) -> bool {
         self.worktree_dir.starts_with(".git/") || self.worktree_dir.starts_with(".git\\")
     }
 }
 
-fn global_config_path() -> Option<PathBuf> {
+pub fn global_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("gwik").join("config.toml"))
 }
 
+pub fn local_config_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("gwik.toml")
+}
+
+/// Parse the global config file strictly, returning unknown keys
+/// (normal loading silently falls back to defaults on errors)
+pub fn check_global_config(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path).context("Failed to read config")?;
+    let config: GlobalConfig = toml::from_str(&content)?;
+    Ok(config.unknown.into_keys().collect())
+}
+
+/// Parse a local gwik.toml strictly, returning unknown keys
+pub fn check_local_config(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path).context("Failed to read config")?;
+    let config: LocalConfig = toml::from_str(&content)?;
+    Ok(config.unknown.into_keys().collect())
+}
+
 fn load_global_config() -> GlobalConfig {
     let Some(path) = global_config_path() else {
         return GlobalConfig::default();
@@ -88,7 +220,7 @@ fn load_global_config() -> GlobalConfig {
 }
 
 fn load_local_config(git_dir: &Path) -> LocalConfig {
-    let path = git_dir.join("gwik.toml");
+    let path = local_config_path(git_dir);
 
     if !path.exists() {
         return LocalConfig::default();
@@ -100,6 +232,50 @@ fn load_local_config(git_dir: &Path) -> LocalConfig {
     }
 }
 
+/// Parse a duration like "30s", "5m", "12h" or "7d" (a bare number is seconds)
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let Ok(number) = number.parse::<u64>() else {
+        bail!("Invalid duration '{}'. Use e.g. 30s, 5m, 12h or 7d", value);
+    };
+    let seconds = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => bail!("Invalid duration '{}'. Use e.g. 30s, 5m, 12h or 7d", value),
+    };
+    match number.checked_mul(seconds) {
+        Some(seconds) => Ok(Duration::from_secs(seconds)),
+        None => bail!("Duration '{}' is too large", value),
+    }
+}
+
+/// Parse an optional duration setting, warning and using `default` when it is invalid
+/// (like the rest of the config, a bad value must not break every command)
+fn duration_or_default(key: &str, value: Option<String>, default: Duration) -> Duration {
+    let Some(value) = value else {
+        return default;
+    };
+    match parse_duration(&value) {
+        Ok(duration) => duration,
+        Err(e) => {
+            eprintln!(
+                "Warning: Invalid {}: {}; using {}s",
+                key,
+                e,
+                default.as_secs()
+            );
+            default
+        }
+    }
+}
+
 fn expand_tilde(path: &str) -> Option<PathBuf> {
     if path.starts_with("~/") {
         dirs::home_dir().map(|home| home.join(&path[2..]))
@@ -118,18 +294,106 @@ mod tests {
     fn test_worktree_dir_in_git() {
         let config = Config {
             worktree_dir: ".git/.worktrees".to_string(),
-            source_worktree: None,
-            roots: vec![],
-            cmds: vec![],
+            ..Default::default()
         };
         assert!(config.worktree_dir_in_git());
 
         let config2 = Config {
             worktree_dir: ".worktrees".to_string(),
-            source_worktree: None,
-            roots: vec![],
-            cmds: vec![],
+            ..Default::default()
         };
         assert!(!config2.worktree_dir_in_git());
     }
+
+    #[test]
+    fn test_is_main_alias() {
+        let mut config = Config::default();
+        assert!(config.is_main_alias("@"));
+        assert!(!config.is_main_alias("main"));
+
+        config.main_alias = Some("main".to_string());
+        assert!(config.is_main_alias("@"));
+        assert!(config.is_main_alias("main"));
+    }
+
+    #[test]
+    fn test_local_overrides_global() {
+        let global: GlobalConfig =
+            toml::from_str("worktree_dir = \".wt\"\nkeep_subdir = false").unwrap();
+        let local: LocalConfig = toml::from_str("keep_subdir = true").unwrap();
+        let config = Config::merge(global, local).unwrap();
+
+        assert_eq!(config.worktree_dir, ".wt");
+        assert!(config.keep_subdir);
+
+        let config = Config::merge(GlobalConfig::default(), LocalConfig::default()).unwrap();
+        assert_eq!(config.worktree_dir, DEFAULT_WORKTREE_DIR);
+        assert!(config.keep_subdir, "keep_subdir defaults to true");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(604800));
+        assert!(parse_duration("5 minutes").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("99999999999999999d").is_err());
+    }
+
+    #[test]
+    fn test_invalid_duration_falls_back() {
+        let local: LocalConfig = toml::from_str("fetch_timeout = \"soon\"").unwrap();
+        let config = Config::merge(GlobalConfig::default(), local).unwrap();
+        assert_eq!(config.fetch_timeout, DEFAULT_FETCH_TIMEOUT);
+    }
+
+    #[test]
+    fn test_cmds() {
+        let local: LocalConfig = toml::from_str(
+            "cmds = [\"make\", { run = \"npm install\", cwd = \"dst\", timeout = \"5m\" }]",
+        )
+        .unwrap();
+        let config = Config::merge(GlobalConfig::default(), local).unwrap();
+        assert_eq!(config.cmds[0].run, "make");
+        assert_eq!(config.cmds[0].cwd, None);
+        assert_eq!(config.cmds[1].cwd.as_deref(), Some("dst"));
+        assert_eq!(config.cmds[1].timeout.as_deref(), Some("5m"));
+
+        let local: LocalConfig =
+            toml::from_str("cmds = [{ run = \"x\", timeout = \"soon\" }]").unwrap();
+        // Invalid timeouts are reported when the command runs, not on load
+        assert!(Config::merge(GlobalConfig::default(), local).is_ok());
+
+        // Misspelled keys are rejected rather than silently ignored
+        let misspelled = [
+            "cmds = [{ run = \"x\", when = { exist = \"package.json\" } }]",
+            "cmds = [{ run = \"x\", time_out = \"5m\" }]",
+        ];
+        for toml in misspelled {
+            assert!(toml::from_str::<LocalConfig>(toml).is_err(), "{}", toml);
+        }
+    }
+
+    #[test]
+    fn test_hooks() {
+        let local: LocalConfig =
+            toml::from_str("[hooks]\npre-close = [\"docker compose down\"]").unwrap();
+        let config = Config::merge(GlobalConfig::default(), local).unwrap();
+        assert_eq!(config.hooks.pre_close, vec!["docker compose down"]);
+        assert!(config.hooks.post_open.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let local: LocalConfig =
+            toml::from_str("worktree_dirs = \".wt\"\ncmds = [\"make\"]").unwrap();
+        assert!(matches!(&local.cmds[..], [CmdEntry::Plain(cmd)] if cmd == "make"));
+        assert_eq!(
+            local.unknown.keys().collect::<Vec<_>>(),
+            vec!["worktree_dirs"]
+        );
+    }
 }
@@ -0,0 +1,56 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+
+/// Move every entry of `from` into `to` except `skip`, undoing the moves on failure
+pub fn move_entries(from: &Path, to: &Path, skip: &[&str]) -> Result<()> {
+    let names: Vec<OsString> = fs::read_dir(from)?
+        .map(|entry| entry.map(|e| e.file_name()))
+        .collect::<io::Result<_>>()?;
+
+    let mut moved = Vec::new();
+    for name in names {
+        if skip.iter().any(|s| name == *s) {
+            continue;
+        }
+
+        let result = if to.join(&name).exists() {
+            Err(anyhow::anyhow!(
+                "{} already exists",
+                to.join(&name).display()
+            ))
+        } else {
+            fs::rename(from.join(&name), to.join(&name))
+                .with_context(|| format!("Failed to move {}", from.join(&name).display()))
+        };
+
+        if let Err(e) = result {
+            for name in moved.iter().rev() {
+                let _ = fs::rename(to.join(name), from.join(name));
+            }
+            return Err(e);
+        }
+        moved.push(name);
+    }
+
+    Ok(())
+}
+
+/// Create the worktree directory, with a .gitignore unless it is inside .git
+pub fn ensure_worktree_dir(worktree_dir: &Path, config: &Config) -> Result<()> {
+    if !worktree_dir.exists() {
+        fs::create_dir_all(worktree_dir)?;
+
+        // Create .gitignore if not inside .git
+        if !config.worktree_dir_in_git() {
+            let gitignore_path = worktree_dir.join(".gitignore");
+            fs::write(&gitignore_path, "*\n")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::git::GitRepo;
+
+/// Hosting service a remote belongs to, which decides where pull requests live
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    /// Pull requests under refs/pull/<n>/head
+    Github,
+    /// Merge requests under refs/merge-requests/<n>/head
+    Gitlab,
+}
+
+impl Forge {
+    /// Use the configured forge, or guess it from the remote URL
+    pub fn resolve(repo: &GitRepo, configured: Option<Forge>, remote: &str) -> Result<Self> {
+        if let Some(forge) = configured {
+            return Ok(forge);
+        }
+        let url = repo
+            .config_get(&format!("remote.{}.url", remote))?
+            .with_context(|| format!("Remote '{}' not found", remote))?;
+        Ok(Self::detect(&url))
+    }
+
+    fn detect(url: &str) -> Self {
+        if url.contains("gitlab") {
+            Forge::Gitlab
+        } else {
+            Forge::Github
+        }
+    }
+
+    /// Ref on the remote holding the head of a pull request
+    pub fn pull_ref(self, number: u64) -> String {
+        match self {
+            Forge::Github => format!("refs/pull/{}/head", number),
+            Forge::Gitlab => format!("refs/merge-requests/{}/head", number),
+        }
+    }
+}
+
+/// A pull request checked out in a worktree, recorded in the branch config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub remote: String,
+}
+
+impl PullRequest {
+    /// Parse the `pr/<n>` shorthand
+    pub fn parse_shorthand(name: &str) -> Option<u64> {
+        name.strip_prefix("pr/")?.parse().ok()
+    }
+
+    /// Default local branch name for a pull request
+    pub fn branch_name(&self) -> String {
+        format!("pr-{}", self.number)
+    }
+
+    /// Fetch the pull request head, returning its commit
+    pub fn fetch(&self, repo: &GitRepo, forge: Forge, timeout: Duration) -> Result<String> {
+        repo.fetch_ref(&self.remote, &forge.pull_ref(self.number), timeout)
+            .with_context(|| format!("Failed to fetch PR #{} from {}", self.number, self.remote))
+    }
+
+    /// Remember that `branch` tracks this pull request
+    pub fn record(&self, repo: &GitRepo, branch: &str) -> Result<()> {
+        repo.config_set(
+            &format!("branch.{}.gwik-pr", branch),
+            &self.number.to_string(),
+        )?;
+        repo.config_set(&format!("branch.{}.gwik-pr-remote", branch), &self.remote)
+    }
+
+    /// The pull request recorded for `branch`, if any
+    pub fn of_branch(repo: &GitRepo, branch: &str) -> Result<Option<Self>> {
+        let Some(number) = repo.config_get(&format!("branch.{}.gwik-pr", branch))? else {
+            return Ok(None);
+        };
+        let Ok(number) = number.parse() else {
+            return Ok(None);
+        };
+        let remote = repo
+            .config_get(&format!("branch.{}.gwik-pr-remote", branch))?
+            .unwrap_or_else(|| "origin".to_string());
+        Ok(Some(Self { number, remote }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_ref() {
+        assert_eq!(Forge::Github.pull_ref(12), "refs/pull/12/head");
+        assert_eq!(Forge::Gitlab.pull_ref(12), "refs/merge-requests/12/head");
+        assert_eq!(
+            Forge::detect("git@gitlab.example.com:a/b.git"),
+            Forge::Gitlab
+        );
+        assert_eq!(Forge::detect("https://github.com/a/b"), Forge::Github);
+    }
+
+    #[test]
+    fn test_parse_shorthand() {
+        assert_eq!(PullRequest::parse_shorthand("pr/123"), Some(123));
+        assert_eq!(PullRequest::parse_shorthand("pr/abc"), None);
+        assert_eq!(PullRequest::parse_shorthand("feature/pr"), None);
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
+use serde::Serialize;
 
 pub struct GitRepo {
     pub root: PathBuf,
@@ -22,7 +27,7 @@ impl GitRepo {
             bail!("Not in a git repository");
         }
 
-        let is_bare = String::from_utf8_lossy(&is_bare_output.stdout)
+        let mut is_bare = String::from_utf8_lossy(&is_bare_output.stdout)
             .trim()
             .eq("true");
 
@@ -41,6 +46,12 @@ impl GitRepo {
             .canonicalize()
             .context("Failed to resolve git directory")?;
 
+        // Inside a linked worktree of a bare repo, rev-parse reports false,
+        // but the shared config still marks the repository as bare
+        if !is_bare {
+            is_bare = config_bool(&git_dir, "core.bare")?.unwrap_or(false);
+        }
+
         // In a bare repo, the git directory IS the root
         // In a regular repo, the root is the parent of the .git directory
         let root = if is_bare {
@@ -59,6 +70,34 @@ impl GitRepo {
         })
     }
 
+    /// Clone a repository as bare into `dest` (which must not exist yet)
+    pub fn clone_bare(url: &str, dest: &Path) -> Result<Self> {
+        let output = Command::new("git")
+            .arg("clone")
+            .arg("--bare")
+            .arg("--quiet")
+            .arg(url)
+            .arg(dest)
+            .output()
+            .context("Failed to run git clone")?;
+
+        if !output.status.success() {
+            bail!(
+                "git clone failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let root = dest
+            .canonicalize()
+            .context("Failed to resolve cloned repository")?;
+        Ok(Self {
+            git_dir: root.clone(),
+            root,
+            is_bare: true,
+        })
+    }
+
     /// Get worktree directory path based on config
     pub fn worktree_dir(&self, worktree_dir_name: &str) -> PathBuf {
         self.root.join(worktree_dir_name)
@@ -83,6 +122,30 @@ impl GitRepo {
 
     /// List all worktrees (excluding main)
     pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        // Filter out the main worktree
+        let worktrees = self
+            .list_all_worktrees()?
+            .into_iter()
+            .filter(|wt| wt.path != self.root)
+            .collect();
+        Ok(worktrees)
+    }
+
+    /// Get the main checkout
+    /// For bare repos this is the configured source_worktree, if any
+    pub fn main_worktree(&self, source_worktree: Option<&str>) -> Result<Option<WorktreeInfo>> {
+        if self.is_bare && source_worktree.is_none() {
+            return Ok(None);
+        }
+        let main_path = self.working_dir(source_worktree);
+        Ok(self
+            .list_all_worktrees()?
+            .into_iter()
+            .find(|wt| wt.path == main_path))
+    }
+
+    /// List all worktrees including main (listed first)
+    pub fn list_all_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
         let output = Command::new("git")
             .current_dir(&self.root)
             .args(["worktree", "list", "--porcelain"])
@@ -97,8 +160,7 @@ impl GitRepo {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let worktrees = parse_worktree_list(&stdout, &self.root);
-        Ok(worktrees)
+        Ok(parse_worktree_list(&stdout))
     }
 
     /// Check if a branch exists locally
@@ -117,26 +179,86 @@ impl GitRepo {
         Ok(status.success())
     }
 
-    /// Check if a remote branch exists
-    pub fn remote_branch_exists(&self, branch: &str) -> Result<Option<String>> {
-        // Check if it's in the format "origin/branch"
-        if branch.contains('/') {
-            let status = Command::new("git")
-                .current_dir(&self.root)
-                .args([
-                    "show-ref",
-                    "--verify",
-                    "--quiet",
-                    &format!("refs/remotes/{}", branch),
-                ])
-                .status()
-                .context("Failed to run git show-ref")?;
-
-            if status.success() {
-                return Ok(Some(branch.to_string()));
+    /// Resolve a name to a remote-tracking branch
+    /// Accepts `<remote>/<branch>`, or a plain branch name that exists on a remote
+    /// but not locally (like `git switch --guess`). Several matching remotes are
+    /// an error unless `checkout.defaultRemote` picks one
+    pub fn resolve_remote_branch(&self, name: &str) -> Result<Option<RemoteBranch>> {
+        let branches = self.remote_branches()?;
+
+        if let Some(rb) = branches.iter().find(|rb| rb.full_name() == name) {
+            return Ok(Some(rb.clone()));
+        }
+        if self.branch_exists(name)? {
+            return Ok(None);
+        }
+
+        let candidates: Vec<_> = branches
+            .into_iter()
+            .filter(|rb| rb.branch == name)
+            .collect();
+        if candidates.len() <= 1 {
+            return Ok(candidates.into_iter().next());
+        }
+
+        if let Some(default) = self.config_get("checkout.defaultRemote")? {
+            if let Some(rb) = candidates.iter().find(|rb| rb.remote == default) {
+                return Ok(Some(rb.clone()));
             }
         }
-        Ok(None)
+
+        let names: Vec<_> = candidates.iter().map(|rb| rb.full_name()).collect();
+        bail!(
+            "'{}' matches branches on several remotes: {}. Use <remote>/{} or set checkout.defaultRemote",
+            name,
+            names.join(", "),
+            name
+        );
+    }
+
+    /// List configured remotes
+    pub fn remotes(&self) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .arg("remote")
+            .output()
+            .context("Failed to run git remote")?;
+
+        if !output.status.success() {
+            bail!(
+                "git remote failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// List remote-tracking branches, split into remote and branch
+    pub fn remote_branches(&self) -> Result<Vec<RemoteBranch>> {
+        let remotes = self.remotes()?;
+
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["for-each-ref", "--format=%(refname)", "refs/remotes/"])
+            .output()
+            .context("Failed to run git for-each-ref")?;
+
+        if !output.status.success() {
+            bail!(
+                "git for-each-ref failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|refname| split_remote_ref(refname, &remotes))
+            .filter(|rb| rb.branch != "HEAD")
+            .collect())
     }
 
     /// Create a worktree with a new branch
@@ -157,6 +279,33 @@ impl GitRepo {
         Ok(())
     }
 
+    /// Create a worktree with a new branch starting at `start`
+    /// The branch does not track `start`, even if it is a remote branch
+    pub fn create_worktree_at(&self, path: &Path, branch: &str, start: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args([
+                "worktree",
+                "add",
+                "--no-track",
+                "-b",
+                branch,
+                path.to_str().unwrap(),
+                start,
+            ])
+            .output()
+            .context("Failed to run git worktree add")?;
+
+        if !output.status.success() {
+            bail!(
+                "git worktree add failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
     /// Create a worktree with an existing branch
     pub fn create_worktree_existing_branch(&self, path: &Path, branch: &str) -> Result<()> {
         let output = Command::new("git")
@@ -175,6 +324,116 @@ impl GitRepo {
         Ok(())
     }
 
+    /// Register a worktree for a branch without checking out files,
+    /// even if the branch is checked out elsewhere
+    pub fn create_worktree_no_checkout(&self, path: &Path, branch: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["worktree", "add", "--no-checkout", "--force"])
+            .arg(path)
+            .arg(branch)
+            .output()
+            .context("Failed to run git worktree add")?;
+
+        if !output.status.success() {
+            bail!(
+                "git worktree add failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Commit a local branch points at, if the branch exists
+    pub fn branch_tip(&self, branch: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args([
+                "rev-parse",
+                "--quiet",
+                "--verify",
+                &format!("refs/heads/{}^{{commit}}", branch),
+            ])
+            .output()
+            .context("Failed to run git rev-parse")?;
+
+        let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !sha.is_empty()).then_some(sha))
+    }
+
+    /// Whether `ancestor` is reachable from `commit`
+    pub fn is_ancestor(&self, ancestor: &str, commit: &str) -> Result<bool> {
+        let status = Command::new("git")
+            .current_dir(&self.root)
+            .args(["merge-base", "--is-ancestor", ancestor, commit])
+            .status()
+            .context("Failed to run git merge-base")?;
+        Ok(status.success())
+    }
+
+    /// Create or move a ref, e.g. to keep a commit from being garbage collected
+    pub fn update_ref(&self, refname: &str, commit: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["update-ref", refname, commit])
+            .output()
+            .context("Failed to run git update-ref")?;
+
+        if !output.status.success() {
+            bail!(
+                "git update-ref failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+
+    /// Delete a ref (a missing ref is not an error)
+    pub fn delete_ref(&self, refname: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["update-ref", "-d", refname])
+            .output()
+            .context("Failed to run git update-ref")?;
+
+        if !output.status.success() {
+            bail!(
+                "git update-ref -d failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+
+    /// Register a worktree without checking out files: for `branch` if it
+    /// exists, as a new branch at `start` otherwise, or detached at `start`
+    pub fn create_worktree_unpopulated(
+        &self,
+        path: &Path,
+        branch: Option<&str>,
+        start: &str,
+    ) -> Result<()> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.root)
+            .args(["worktree", "add", "--no-checkout"]);
+        match branch {
+            Some(branch) if self.branch_exists(branch)? => cmd.arg(path).arg(branch),
+            Some(branch) => cmd.args(["-b", branch]).arg(path).arg(start),
+            None => cmd.arg("--detach").arg(path).arg(start),
+        };
+        let output = cmd.output().context("Failed to run git worktree add")?;
+
+        if !output.status.success() {
+            bail!(
+                "git worktree add failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
     /// Create a worktree tracking a remote branch
     pub fn create_worktree_tracking(
         &self,
@@ -205,79 +464,1081 @@ impl GitRepo {
         Ok(())
     }
 
-    /// Remove a worktree
-    pub fn remove_worktree(&self, path: &Path) -> Result<()> {
+    /// Rename a local branch (also updates worktrees that have it checked out)
+    pub fn rename_branch(&self, old: &str, new: &str) -> Result<()> {
         let output = Command::new("git")
             .current_dir(&self.root)
-            .args(["worktree", "remove", path.to_str().unwrap()])
+            .args(["branch", "-m", old, new])
             .output()
-            .context("Failed to run git worktree remove")?;
+            .context("Failed to run git branch -m")?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("contains modified or untracked files") {
-                bail!("Cannot remove worktree: uncommitted changes exist");
-            }
-            bail!("git worktree remove failed: {}", stderr);
+            bail!(
+                "git branch -m failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
         }
 
         Ok(())
     }
 
-    /// Check if the current directory is inside a worktree
-    pub fn current_worktree(&self) -> Result<Option<PathBuf>> {
-        let cwd = std::env::current_dir()?;
-        let worktrees = self.list_worktrees()?;
+    /// Move a worktree to a new path
+    pub fn move_worktree(&self, from: &Path, to: &Path) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args([
+                "worktree",
+                "move",
+                from.to_str().unwrap(),
+                to.to_str().unwrap(),
+            ])
+            .output()
+            .context("Failed to run git worktree move")?;
 
-        for wt in worktrees {
-            if cwd.starts_with(&wt.path) {
-                return Ok(Some(wt.path));
-            }
+        if !output.status.success() {
+            bail!(
+                "git worktree move failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
         }
 
-        Ok(None)
+        Ok(())
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct WorktreeInfo {
-    pub path: PathBuf,
-}
+    /// Read a repository config value
+    pub fn config_get(&self, key: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["config", "--get", key])
+            .output()
+            .context("Failed to run git config")?;
 
-fn parse_worktree_list(output: &str, main_root: &Path) -> Vec<WorktreeInfo> {
-    let mut worktrees = Vec::new();
-    let mut current_path: Option<PathBuf> = None;
+        if !output.status.success() {
+            return Ok(None);
+        }
 
-    for line in output.lines() {
-        if let Some(path_str) = line.strip_prefix("worktree ") {
-            // Save previous worktree if exists
-            if let Some(path) = current_path.take() {
-                worktrees.push(WorktreeInfo { path });
+        Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ))
+    }
+
+    /// Write a repository config value
+    pub fn config_set(&self, key: &str, value: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["config", key, value])
+            .output()
+            .context("Failed to run git config")?;
+
+        if !output.status.success() {
+            bail!(
+                "git config failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Fetch one remote (optionally a single refspec) or all remotes, giving up after `timeout`
+    pub fn fetch_with_timeout(
+        &self,
+        remote: Option<&str>,
+        refspec: Option<&str>,
+        timeout: Duration,
+    ) -> Result<()> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.root)
+            // Never wait on a credential prompt
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .args(["fetch", "--quiet"]);
+        match remote {
+            Some(remote) => cmd.arg(remote).args(refspec),
+            None => cmd.arg("--all"),
+        };
+
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run git fetch")?;
+
+        // Drain stderr while waiting so a chatty fetch cannot fill the pipe and stall
+        let pipe = child.stderr.take();
+        let reader = thread::spawn(move || {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            stderr
+        });
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                if status.success() {
+                    return Ok(());
+                }
+                let stderr = reader.join().unwrap_or_default();
+                bail!("git fetch failed: {}", stderr.trim());
+            }
+            if Instant::now() >= deadline {
+                // The reader is left behind: helpers like ssh may keep the pipe open
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("git fetch timed out after {}s", timeout.as_secs());
             }
-            current_path = Some(PathBuf::from(path_str));
+            thread::sleep(Duration::from_millis(50));
         }
     }
 
-    // Don't forget the last worktree
-    if let Some(path) = current_path {
-        worktrees.push(WorktreeInfo { path });
+    /// Push a branch and set it up to track the pushed branch
+    pub fn push_branch(&self, remote: &str, branch: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .args(["push", "--quiet", "--set-upstream", remote, branch])
+            .output()
+            .context("Failed to run git push")?;
+
+        if !output.status.success() {
+            bail!(
+                "git push failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
     }
 
-    // Filter out the main worktree
-    worktrees
-        .into_iter()
-        .filter(|wt| wt.path != main_root)
-        .collect()
-}
+    /// Make `branch` track the branch of the same name on `remote`, even before it is pushed
+    pub fn set_upstream(&self, branch: &str, remote: &str) -> Result<()> {
+        self.config_set(&format!("branch.{}.remote", branch), remote)?;
+        self.config_set(
+            &format!("branch.{}.merge", branch),
+            &format!("refs/heads/{}", branch),
+        )
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Fetch a single ref from a remote without storing it, returning the fetched commit
+    pub fn fetch_ref(&self, remote: &str, refname: &str, timeout: Duration) -> Result<String> {
+        self.fetch_with_timeout(Some(remote), Some(refname), timeout)?;
 
-    #[test]
-    fn test_branch_to_dirname() {
-        assert_eq!(GitRepo::branch_to_dirname("feature/login"), "feature-login");
-        assert_eq!(GitRepo::branch_to_dirname("bugfix-auth"), "bugfix-auth");
-        assert_eq!(GitRepo::branch_to_dirname("a/b/c"), "a-b-c");
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["rev-parse", "FETCH_HEAD"])
+            .output()
+            .context("Failed to run git rev-parse")?;
+
+        if !output.status.success() {
+            bail!(
+                "git rev-parse FETCH_HEAD failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Repair worktree administrative files, given the current worktree paths
+    pub fn repair_worktrees(&self, paths: &[PathBuf]) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["worktree", "repair"])
+            .args(paths)
+            .output()
+            .context("Failed to run git worktree repair")?;
+
+        if !output.status.success() {
+            bail!(
+                "git worktree repair failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Drop records of worktrees whose directories are gone
+    pub fn prune_worktrees(&self) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["worktree", "prune"])
+            .output()
+            .context("Failed to run git worktree prune")?;
+
+        if !output.status.success() {
+            bail!(
+                "git worktree prune failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Remove a worktree; `force` discards uncommitted changes and untracked files
+    pub fn remove_worktree(&self, path: &Path, force: bool) -> Result<()> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.root).args(["worktree", "remove"]);
+        if force {
+            cmd.arg("--force");
+        }
+        let output = cmd
+            .arg(path)
+            .output()
+            .context("Failed to run git worktree remove")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("contains modified or untracked files") {
+                bail!("Cannot remove worktree: uncommitted changes exist");
+            }
+            bail!("git worktree remove failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Fetch all remotes
+    /// Returns false if the repository has no remotes
+    pub fn fetch_all(&self) -> Result<bool> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .arg("remote")
+            .output()
+            .context("Failed to run git remote")?;
+
+        if String::from_utf8_lossy(&output.stdout).trim().is_empty() {
+            return Ok(false);
+        }
+
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["fetch", "--all", "--prune", "--quiet"])
+            .output()
+            .context("Failed to run git fetch")?;
+
+        if !output.status.success() {
+            bail!(
+                "git fetch failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Count stash entries per branch they were created on
+    pub fn stash_counts(&self) -> Result<HashMap<String, usize>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["stash", "list", "--format=%gs"])
+            .output()
+            .context("Failed to run git stash list")?;
+
+        let mut counts = HashMap::new();
+        if !output.status.success() {
+            return Ok(counts);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            if let Some(branch) = stash_branch(line) {
+                *counts.entry(branch.to_string()).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Short name of the branch HEAD points to (the default branch of a bare clone)
+    pub fn head_branch(&self) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .output()
+            .context("Failed to run git symbolic-ref")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!branch.is_empty()).then_some(branch))
+    }
+
+    /// Point a symbolic ref (e.g. refs/remotes/origin/HEAD) at another ref
+    pub fn set_symbolic_ref(&self, name: &str, target: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args(["symbolic-ref", name, target])
+            .output()
+            .context("Failed to run git symbolic-ref")?;
+
+        if !output.status.success() {
+            bail!(
+                "git symbolic-ref failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Ref that branches are merged into: the remote default branch if known,
+    /// otherwise the branch of the main checkout
+    pub fn default_base(&self, source_worktree: Option<&str>) -> Result<Option<String>> {
+        if let Some(base) = self.remote_default_branch()? {
+            return Ok(Some(base));
+        }
+
+        Ok(self
+            .main_worktree(source_worktree)?
+            .and_then(|main| main.branch))
+    }
+
+    /// The remote default branch (`origin/HEAD`), e.g. `origin/main`
+    pub fn remote_default_branch(&self) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.root)
+            .args([
+                "symbolic-ref",
+                "--quiet",
+                "--short",
+                "refs/remotes/origin/HEAD",
+            ])
+            .output()
+            .context("Failed to run git symbolic-ref")?;
+
+        let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !base.is_empty()).then_some(base))
+    }
+
+    /// Get the top-level directory of the checkout containing the current directory
+    /// Unlike `current_worktree`, this includes the main checkout
+    pub fn current_checkout(&self) -> Result<Option<PathBuf>> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .output()
+            .context("Failed to run git rev-parse")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let toplevel = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if toplevel.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PathBuf::from(toplevel)))
+    }
+
+    /// Check if the current directory is inside a worktree
+    pub fn current_worktree(&self) -> Result<Option<PathBuf>> {
+        let cwd = std::env::current_dir()?;
+        let worktrees = self.list_worktrees()?;
+
+        for wt in worktrees {
+            if cwd.starts_with(&wt.path) {
+                return Ok(Some(wt.path));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Read a boolean config value from a repository
+fn config_bool(git_dir: &Path, key: &str) -> Result<Option<bool>> {
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args(["config", "--bool", "--get", key])
+        .output()
+        .context("Failed to run git config")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim() == "true",
+    ))
+}
+
+/// A remote-tracking branch (refs/remotes/<remote>/<branch>)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteBranch {
+    pub remote: String,
+    pub branch: String,
+}
+
+impl RemoteBranch {
+    /// `<remote>/<branch>`, as accepted by git commands
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.remote, self.branch)
+    }
+}
+
+/// Split a remote-tracking ref name using the longest matching remote name,
+/// so remotes containing slashes are handled
+pub fn split_remote_ref(refname: &str, remotes: &[String]) -> Option<RemoteBranch> {
+    let rest = refname.strip_prefix("refs/remotes/")?;
+    let remote = remotes
+        .iter()
+        .filter(|r| {
+            rest.strip_prefix(r.as_str())
+                .is_some_and(|b| b.starts_with('/'))
+        })
+        .max_by_key(|r| r.len())?;
+
+    Some(RemoteBranch {
+        remote: remote.clone(),
+        branch: rest[remote.len() + 1..].to_string(),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct WorktreeInfo {
+    pub path: PathBuf,
+    /// Commit checked out (None for bare entries)
+    pub head: Option<String>,
+    /// Short branch name (None when detached or bare)
+    pub branch: Option<String>,
+    /// Lock reason, if the worktree is locked (may be empty)
+    pub locked: Option<String>,
+    /// Reason git considers the worktree prunable
+    pub prunable: Option<String>,
+}
+
+impl WorktreeInfo {
+    /// Directory name of the worktree
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Check if the worktree has uncommitted changes or untracked files
+    pub fn is_dirty(&self) -> Result<bool> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["status", "--porcelain"])
+            .output()
+            .context("Failed to run git status")?;
+
+        if !output.status.success() {
+            bail!(
+                "git status failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(!output.stdout.is_empty())
+    }
+
+    /// Committer timestamp (seconds since epoch) of the checked out commit
+    pub fn last_commit_time(&self) -> Result<Option<u64>> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["log", "-1", "--format=%ct"])
+            .output()
+            .context("Failed to run git log")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    }
+
+    /// Count commits of HEAD that are on no remote-tracking branch
+    pub fn unpushed_commits(&self) -> Result<u32> {
+        self.count_commits_not_in(&["--remotes"])
+    }
+
+    /// Count commits of HEAD that no local or remote-tracking branch contains
+    /// (a detached HEAD at such a commit is lost once the worktree is gone)
+    pub fn unreachable_commits(&self) -> Result<u32> {
+        self.count_commits_not_in(&["--branches", "--remotes"])
+    }
+
+    fn count_commits_not_in(&self, refs: &[&str]) -> Result<u32> {
+        if self.is_unborn()? {
+            return Ok(0);
+        }
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rev-list", "--count", "HEAD", "--not"])
+            .args(refs)
+            .output()
+            .context("Failed to run git rev-list")?;
+
+        if !output.status.success() {
+            bail!(
+                "git rev-list failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap_or(0))
+    }
+
+    /// Count commits reachable from `to` but not from `from`
+    pub fn count_commits(&self, from: &str, to: &str) -> Result<u32> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rev-list", "--count", &format!("{}..{}", from, to)])
+            .output()
+            .context("Failed to run git rev-list")?;
+
+        if !output.status.success() {
+            bail!(
+                "git rev-list failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap_or(0))
+    }
+
+    /// Rebase the checked out branch onto `onto`
+    /// Returns false if the rebase stopped on conflicts (left in progress)
+    pub fn rebase(&self, onto: &str) -> Result<bool> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rebase", onto])
+            .output()
+            .context("Failed to run git rebase")?;
+
+        self.conflict_or_error(&output, "rebase")
+    }
+
+    /// Merge `from` into the checked out branch
+    /// Returns false if the merge stopped on conflicts (left in progress)
+    pub fn merge(&self, from: &str) -> Result<bool> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["merge", "--no-edit", from])
+            .output()
+            .context("Failed to run git merge")?;
+
+        self.conflict_or_error(&output, "merge")
+    }
+
+    /// Fast-forward the current branch to `to`, failing if that is not possible
+    pub fn fast_forward(&self, to: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["merge", "--ff-only", "--quiet", to])
+            .output()
+            .context("Failed to run git merge")?;
+
+        if !output.status.success() {
+            bail!(
+                "git merge --ff-only failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Reset the index to HEAD, leaving the files alone
+    pub fn reset_index(&self) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["reset", "--quiet"])
+            .output()
+            .context("Failed to run git reset")?;
+
+        if !output.status.success() {
+            bail!(
+                "git reset failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Changed and untracked files, as `git status --short` lines
+    pub fn changed_files(&self) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["status", "--porcelain", "--untracked-files=all"])
+            .output()
+            .context("Failed to run git status")?;
+
+        if !output.status.success() {
+            bail!(
+                "git status failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Binary diff from HEAD to the working tree, untracked files included
+    /// Uses a temporary index, so the worktree's own index is left alone
+    pub fn changes_patch(&self) -> Result<Vec<u8>> {
+        let index = std::env::temp_dir().join(format!("gwik-index-{}", std::process::id()));
+        let result = self.diff_all_with_index(&index);
+        let _ = std::fs::remove_file(&index);
+        result
+    }
+
+    fn diff_all_with_index(&self, index: &Path) -> Result<Vec<u8>> {
+        // On an unborn branch everything is new: start from an empty index
+        if self.is_unborn()? {
+            self.git_with_index(index, &["add", "--all"])?;
+            return self.git_with_index(index, &["diff", "--cached", "--binary"]);
+        }
+        self.git_with_index(index, &["read-tree", "HEAD"])?;
+        self.git_with_index(index, &["add", "--all"])?;
+        self.git_with_index(index, &["diff", "--cached", "--binary", "HEAD"])
+    }
+
+    /// Check if HEAD is on a branch with no commits yet
+    pub fn is_unborn(&self) -> Result<bool> {
+        let status = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rev-parse", "--quiet", "--verify", "HEAD"])
+            .stdout(Stdio::null())
+            .status()
+            .context("Failed to run git rev-parse")?;
+
+        Ok(!status.success())
+    }
+
+    /// Run git in the worktree with another index file, returning stdout
+    fn git_with_index(&self, index: &Path, args: &[&str]) -> Result<Vec<u8>> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .env("GIT_INDEX_FILE", index)
+            .args(args)
+            .output()
+            .context("Failed to run git")?;
+
+        if !output.status.success() {
+            bail!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// Stash staged and unstaged changes (and untracked files if asked)
+    /// Returns the stash commit, or None if there was nothing to stash
+    pub fn stash_push(&self, include_untracked: bool, message: &str) -> Result<Option<String>> {
+        let before = self.stash_top()?;
+
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.path)
+            .args(["stash", "push", "--quiet", "-m", message]);
+        if include_untracked {
+            cmd.arg("--include-untracked");
+        }
+        let output = cmd.output().context("Failed to run git stash push")?;
+
+        if !output.status.success() {
+            bail!(
+                "git stash push failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let after = self.stash_top()?;
+        Ok(if after != before { after } else { None })
+    }
+
+    /// Apply a stash commit, restoring its index state too
+    pub fn stash_apply(&self, stash: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["stash", "apply", "--index", "--quiet", stash])
+            .output()
+            .context("Failed to run git stash apply")?;
+
+        if !output.status.success() {
+            bail!(
+                "git stash apply failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Remove a stash commit from the stash list (no-op if it is not there)
+    pub fn stash_drop(&self, stash: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["stash", "list", "--format=%H"])
+            .output()
+            .context("Failed to run git stash list")?;
+
+        let list = String::from_utf8_lossy(&output.stdout);
+        let Some(index) = list.lines().position(|sha| sha == stash) else {
+            return Ok(());
+        };
+
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["stash", "drop", "--quiet", &format!("stash@{{{}}}", index)])
+            .output()
+            .context("Failed to run git stash drop")?;
+
+        if !output.status.success() {
+            bail!(
+                "git stash drop failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Throw away all changes and untracked files (ignored files are kept)
+    pub fn discard_changes(&self) -> Result<()> {
+        for args in [
+            &["reset", "--hard", "--quiet"][..],
+            &["clean", "-fd", "--quiet"],
+        ] {
+            let output = Command::new("git")
+                .current_dir(&self.path)
+                .args(args)
+                .output()
+                .context("Failed to run git")?;
+
+            if !output.status.success() {
+                bail!(
+                    "git {} failed: {}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Commit at the top of the stash, if any
+    fn stash_top(&self) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rev-parse", "--quiet", "--verify", "refs/stash"])
+            .output()
+            .context("Failed to run git rev-parse")?;
+
+        let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !sha.is_empty()).then_some(sha))
+    }
+
+    /// Check if a rebase, merge, cherry-pick or revert is in progress
+    pub fn operation_in_progress(&self) -> Result<bool> {
+        for marker in [
+            "rebase-merge",
+            "rebase-apply",
+            "MERGE_HEAD",
+            "CHERRY_PICK_HEAD",
+            "REVERT_HEAD",
+        ] {
+            let output = Command::new("git")
+                .current_dir(&self.path)
+                .args(["rev-parse", "--path-format=absolute", "--git-path", marker])
+                .output()
+                .context("Failed to run git rev-parse")?;
+            let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+            if output.status.success() && path.exists() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Ok(true) on success, Ok(false) if stopped on conflicts, Err otherwise
+    fn conflict_or_error(&self, output: &std::process::Output, operation: &str) -> Result<bool> {
+        if output.status.success() {
+            return Ok(true);
+        }
+        if self.operation_in_progress()? {
+            return Ok(false);
+        }
+        bail!(
+            "git {} failed: {}",
+            operation,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+}
+
+/// Detailed state of a worktree, as shown by `gwik status`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct WorktreeStatus {
+    /// Upstream branch (e.g. origin/main)
+    pub upstream: Option<String>,
+    /// Commits ahead of upstream
+    pub ahead: Option<u32>,
+    /// Commits behind upstream
+    pub behind: Option<u32>,
+    /// An upstream is configured but its branch does not exist (not pushed yet, or deleted)
+    pub upstream_gone: bool,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    /// Stash entries created on this worktree's branch
+    pub stashes: usize,
+    /// Committer timestamp (seconds since epoch) of HEAD
+    pub last_commit_time: Option<u64>,
+    /// Whether the branch is merged into the base (None if unknown or not applicable)
+    pub merged: Option<bool>,
+}
+
+impl WorktreeInfo {
+    /// Query the detailed status of this worktree
+    /// `base` is the ref used to decide whether the branch is merged
+    pub fn status(&self, base: Option<&str>) -> Result<WorktreeStatus> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["status", "--porcelain=v2", "--branch"])
+            .output()
+            .context("Failed to run git status")?;
+
+        if !output.status.success() {
+            bail!(
+                "git status failed in {}: {}",
+                self.path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let mut status = parse_status_v2(&String::from_utf8_lossy(&output.stdout));
+        status.last_commit_time = self.last_commit_time()?;
+
+        if let (Some(branch), Some(base)) = (&self.branch, base) {
+            // The base branch itself is not reported as merged
+            if base != branch && !base.ends_with(&format!("/{}", branch)) {
+                let merged = Command::new("git")
+                    .current_dir(&self.path)
+                    .args(["merge-base", "--is-ancestor", "HEAD", base])
+                    .status()
+                    .context("Failed to run git merge-base")?;
+                status.merged = Some(merged.success());
+            }
+        }
+
+        Ok(status)
+    }
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+fn parse_status_v2(output: &str) -> WorktreeStatus {
+    let mut status = WorktreeStatus::default();
+
+    for line in output.lines() {
+        if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // Format: +<ahead> -<behind>
+            let mut parts = ab.split_whitespace();
+            status.ahead = parts
+                .next()
+                .and_then(|a| a.trim_start_matches('+').parse().ok());
+            status.behind = parts
+                .next()
+                .and_then(|b| b.trim_start_matches('-').parse().ok());
+        } else if line.starts_with("1 ") || line.starts_with("2 ") {
+            // Format: <type> <XY> ... where X is the index and Y the worktree state
+            let xy = line.as_bytes().get(2..4).unwrap_or(b"..");
+            if xy[0] != b'.' {
+                status.staged += 1;
+            }
+            if xy[1] != b'.' {
+                status.unstaged += 1;
+            }
+        } else if line.starts_with("u ") {
+            status.unstaged += 1;
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        }
+    }
+
+    // git omits ahead/behind when the upstream branch is missing
+    status.upstream_gone = status.upstream.is_some() && status.ahead.is_none();
+
+    status
+}
+
+/// Extract the branch from a stash reflog subject ("WIP on <branch>: ..." or "On <branch>: ...")
+fn stash_branch(subject: &str) -> Option<&str> {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))?;
+    rest.split_once(':').map(|(branch, _)| branch)
+}
+
+/// Read a path from a `.git` file ("gitdir: <path>") or an admin `gitdir` file ("<path>")
+pub fn read_gitdir(file: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let line = content.lines().next()?.trim();
+    let path = PathBuf::from(line.strip_prefix("gitdir: ").unwrap_or(line));
+
+    // Relative paths are relative to the directory containing the file
+    let path = if path.is_relative() {
+        file.parent()?.join(path)
+    } else {
+        path
+    };
+    path.exists().then_some(path)
+}
+
+/// Parse `git worktree list --porcelain` output (the main worktree comes first)
+pub fn parse_worktree_list(output: &str) -> Vec<WorktreeInfo> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<WorktreeInfo> = None;
+
+    for line in output.lines() {
+        if let Some(path_str) = line.strip_prefix("worktree ") {
+            // Save previous worktree if exists
+            if let Some(wt) = current.take() {
+                worktrees.push(wt);
+            }
+            current = Some(WorktreeInfo {
+                path: PathBuf::from(path_str),
+                head: None,
+                branch: None,
+                locked: None,
+                prunable: None,
+            });
+        } else if let Some(wt) = current.as_mut() {
+            if let Some(head) = line.strip_prefix("HEAD ") {
+                wt.head = Some(head.to_string());
+            } else if let Some(branch) = line.strip_prefix("branch ") {
+                let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+                wt.branch = Some(branch.to_string());
+            } else if line == "locked" {
+                wt.locked = Some(String::new());
+            } else if let Some(reason) = line.strip_prefix("locked ") {
+                wt.locked = Some(reason.to_string());
+            } else if let Some(reason) = line.strip_prefix("prunable ") {
+                wt.prunable = Some(reason.to_string());
+            }
+        }
+    }
+
+    // Don't forget the last worktree
+    if let Some(wt) = current {
+        worktrees.push(wt);
+    }
+
+    worktrees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_to_dirname() {
+        assert_eq!(GitRepo::branch_to_dirname("feature/login"), "feature-login");
+        assert_eq!(GitRepo::branch_to_dirname("bugfix-auth"), "bugfix-auth");
+        assert_eq!(GitRepo::branch_to_dirname("a/b/c"), "a-b-c");
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
+                      worktree /repo/.worktrees/feature-x\nHEAD def456\nbranch refs/heads/feature/x\n\n\
+                      worktree /repo/.worktrees/detached\nHEAD 789abc\ndetached\nlocked\n\n\
+                      worktree /repo/.worktrees/gone\nHEAD 789abc\nbranch refs/heads/gone\n\
+                      prunable gitdir file points to non-existent location\n";
+        let worktrees = parse_worktree_list(output);
+
+        assert_eq!(worktrees.len(), 4);
+        assert_eq!(worktrees[0].path, PathBuf::from("/repo"));
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/x"));
+        assert_eq!(worktrees[1].head.as_deref(), Some("def456"));
+        assert_eq!(worktrees[1].name(), "feature-x");
+        assert_eq!(worktrees[2].branch, None);
+        assert_eq!(worktrees[2].locked.as_deref(), Some(""));
+        assert_eq!(worktrees[1].locked, None);
+        assert_eq!(
+            worktrees[3].prunable.as_deref(),
+            Some("gitdir file points to non-existent location")
+        );
+    }
+
+    #[test]
+    fn test_split_remote_ref() {
+        let remotes = vec![
+            "origin".to_string(),
+            "upstream/team".to_string(),
+            "upstream".to_string(),
+        ];
+        let split = |r| split_remote_ref(r, &remotes).map(|rb| (rb.remote, rb.branch));
+
+        assert_eq!(
+            split("refs/remotes/origin/feature/x"),
+            Some(("origin".to_string(), "feature/x".to_string()))
+        );
+        assert_eq!(
+            split("refs/remotes/upstream/team/fix"),
+            Some(("upstream/team".to_string(), "fix".to_string()))
+        );
+        assert_eq!(
+            split("refs/remotes/upstream/main"),
+            Some(("upstream".to_string(), "main".to_string()))
+        );
+        assert_eq!(split("refs/remotes/gone/main"), None);
+        assert_eq!(split("refs/heads/main"), None);
+    }
+
+    #[test]
+    fn test_parse_status_v2() {
+        let output = "# branch.oid abc\n# branch.head feature\n\
+                      # branch.upstream origin/feature\n# branch.ab +2 -3\n\
+                      1 M. N... 100644 100644 100644 a b staged.txt\n\
+                      1 .M N... 100644 100644 100644 a b unstaged.txt\n\
+                      1 MM N... 100644 100644 100644 a b both.txt\n\
+                      ? new.txt\n? other.txt\n";
+        let status = parse_status_v2(output);
+
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!(status.ahead, Some(2));
+        assert_eq!(status.behind, Some(3));
+        assert_eq!(status.staged, 2);
+        assert_eq!(status.unstaged, 2);
+        assert_eq!(status.untracked, 2);
+        assert!(!status.upstream_gone);
+
+        let status = parse_status_v2("# branch.head new\n# branch.upstream origin/new\n");
+        assert!(status.upstream_gone);
+        assert!(!parse_status_v2("# branch.head local\n").upstream_gone);
+    }
+
+    #[test]
+    fn test_stash_branch() {
+        assert_eq!(
+            stash_branch("WIP on feature/x: abc123 msg"),
+            Some("feature/x")
+        );
+        assert_eq!(stash_branch("On main: saved work"), Some("main"));
+        assert_eq!(stash_branch("autostash"), None);
     }
 }
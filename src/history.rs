@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of visits kept in the history file
+const MAX_VISITS: usize = 1000;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// A single navigation to a worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Visit {
+    /// Repository root
+    pub repo: PathBuf,
+    /// Worktree directory name (`@` for the main checkout)
+    pub worktree: String,
+    pub path: PathBuf,
+    /// Seconds since epoch
+    pub time: u64,
+}
+
+/// Worktree visit history stored in the gwik state directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    visits: Vec<Visit>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let Some(path) = history_path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = history_path().context("Cannot determine state directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string(self).context("Failed to serialize history")?;
+        fs::write(&path, content).context("Failed to write history")?;
+        Ok(())
+    }
+
+    /// Record a visit to a worktree
+    pub fn record(&mut self, repo: &Path, path: &Path) {
+        let worktree = if path == repo {
+            "@".to_string()
+        } else {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        self.visits.push(Visit {
+            repo: repo.to_path_buf(),
+            worktree,
+            path: path.to_path_buf(),
+            time: now(),
+        });
+
+        if self.visits.len() > MAX_VISITS {
+            let excess = self.visits.len() - MAX_VISITS;
+            self.visits.drain(..excess);
+        }
+    }
+
+    /// Point visits of a worktree that was moved at its new path
+    pub fn rename(&mut self, old: &Path, new: &Path) {
+        let worktree = new
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for visit in self.visits.iter_mut().filter(|v| v.path == old) {
+            visit.path = new.to_path_buf();
+            visit.worktree = worktree.clone();
+        }
+    }
+
+    /// The most recently visited path
+    pub fn last(&self) -> Option<&Path> {
+        self.visits.last().map(|v| v.path.as_path())
+    }
+
+    /// The most recent visit to an existing worktree other than `current`
+    pub fn previous(&self, current: Option<&Path>) -> Option<&Visit> {
+        self.visits
+            .iter()
+            .rev()
+            .find(|v| Some(v.path.as_path()) != current && v.path.exists())
+    }
+
+    /// Existing worktree paths ranked by frecency (most relevant first)
+    pub fn ranked(&self) -> Vec<PathBuf> {
+        let now = now();
+        let mut scores: Vec<(PathBuf, f64)> = Vec::new();
+
+        for visit in &self.visits {
+            let weight = frecency_weight(now.saturating_sub(visit.time));
+            match scores.iter_mut().find(|(p, _)| *p == visit.path) {
+                Some((_, score)) => *score += weight,
+                None => scores.push((visit.path.clone(), weight)),
+            }
+        }
+
+        scores.retain(|(p, _)| p.exists());
+        // Stable sort keeps the first-visited path first on ties
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores.into_iter().map(|(p, _)| p).collect()
+    }
+}
+
+/// Record a visit, ignoring failures (history is best-effort)
+pub fn record_visit(repo: &Path, path: &Path) {
+    let mut history = History::load();
+    history.record(repo, path);
+    let _ = history.save();
+}
+
+/// Follow a moved worktree, ignoring failures (history is best-effort)
+pub fn rename_visits(old: &Path, new: &Path) {
+    let mut history = History::load();
+    history.rename(old, new);
+    let _ = history.save();
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .map(|p| p.join("gwik").join("history.toml"))
+}
+
+/// Weight of a single visit by age, favoring recent visits
+fn frecency_weight(age: u64) -> f64 {
+    if age < HOUR {
+        4.0
+    } else if age < DAY {
+        2.0
+    } else if age < WEEK {
+        0.5
+    } else {
+        0.25
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frecency_weight() {
+        assert!(frecency_weight(0) > frecency_weight(2 * HOUR));
+        assert!(frecency_weight(2 * HOUR) > frecency_weight(2 * DAY));
+        assert!(frecency_weight(2 * DAY) > frecency_weight(2 * WEEK));
+    }
+
+    #[test]
+    fn test_previous_skips_current() {
+        let mut history = History::default();
+        let dir = std::env::temp_dir();
+        history.record(&dir, &dir);
+        history.record(Path::new("/nonexistent"), Path::new("/nonexistent/wt"));
+
+        // Non-existent paths are skipped, and so is the current one
+        assert_eq!(history.previous(None).map(|v| &v.path), Some(&dir));
+        assert!(history.previous(Some(&dir)).is_none());
+        assert_eq!(
+            history.previous(None).map(|v| v.worktree.as_str()),
+            Some("@")
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut history = History::default();
+        history.record(Path::new("/repo"), Path::new("/repo/.worktrees/old"));
+        history.record(Path::new("/repo"), Path::new("/repo/.worktrees/other"));
+        history.rename(
+            Path::new("/repo/.worktrees/old"),
+            Path::new("/repo/.worktrees/new"),
+        );
+
+        assert_eq!(history.visits[0].path, Path::new("/repo/.worktrees/new"));
+        assert_eq!(history.visits[0].worktree, "new");
+        assert_eq!(history.visits[1].worktree, "other");
+    }
+}
@@ -0,0 +1,129 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::git::GitRepo;
+
+/// Hook commands per lifecycle event, from the `[hooks]` table in gwik.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Hooks {
+    #[serde(default)]
+    pub pre_open: Vec<String>,
+    #[serde(default)]
+    pub post_open: Vec<String>,
+    #[serde(default)]
+    pub pre_close: Vec<String>,
+    #[serde(default)]
+    pub post_close: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreOpen,
+    PostOpen,
+    PreClose,
+    PostClose,
+}
+
+impl HookEvent {
+    /// Name used in config, as the hook file name and in GWIK_EVENT
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreOpen => "pre-open",
+            HookEvent::PostOpen => "post-open",
+            HookEvent::PreClose => "pre-close",
+            HookEvent::PostClose => "post-close",
+        }
+    }
+
+    /// Failing `pre-*` hooks abort the operation
+    fn is_pre(self) -> bool {
+        matches!(self, HookEvent::PreOpen | HookEvent::PreClose)
+    }
+}
+
+impl Hooks {
+    fn commands(&self, event: HookEvent) -> &[String] {
+        match event {
+            HookEvent::PreOpen => &self.pre_open,
+            HookEvent::PostOpen => &self.post_open,
+            HookEvent::PreClose => &self.pre_close,
+            HookEvent::PostClose => &self.post_close,
+        }
+    }
+}
+
+/// The worktree a hook runs for
+pub struct HookContext<'a> {
+    /// Main checkout (source worktree for bare repos)
+    pub src: &'a Path,
+    /// Worktree being opened or closed (may not exist yet, or anymore)
+    pub dst: &'a Path,
+    pub branch: Option<&'a str>,
+}
+
+impl HookContext<'_> {
+    /// Set the documented GWIK_* variables (except GWIK_EVENT)
+    pub fn set_env(&self, command: &mut Command, repo: &GitRepo) {
+        command
+            .env("GWIK_SRC", self.src)
+            .env("GWIK_DST", self.dst)
+            .env("GWIK_BRANCH", self.branch.unwrap_or_default())
+            .env("GWIK_REPO", &repo.root);
+    }
+}
+
+/// Directory of executable hook files, named after the event
+pub fn hooks_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("gwik").join("hooks")
+}
+
+/// Run the configured hook commands for `event`, then the hook file if present
+/// A failing `pre-*` hook returns an error; a failing `post-*` hook only warns
+pub fn run(repo: &GitRepo, hooks: &Hooks, event: HookEvent, ctx: &HookContext) -> Result<()> {
+    let mut commands: Vec<(String, Command)> = hooks
+        .commands(event)
+        .iter()
+        .map(|cmd| {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(cmd);
+            (cmd.clone(), command)
+        })
+        .collect();
+
+    let file = hooks_dir(repo).join(event.name());
+    if file.is_file() {
+        commands.push((file.display().to_string(), Command::new(&file)));
+    }
+
+    // Before open and after close the worktree does not exist
+    let cwd = if ctx.dst.is_dir() { ctx.dst } else { ctx.src };
+
+    for (label, mut command) in commands {
+        eprintln!("Running {} hook: {}", event.name(), label);
+
+        ctx.set_env(&mut command, repo);
+        let result = command
+            .current_dir(cwd)
+            .env("GWIK_EVENT", event.name())
+            // stdout is reserved for the cd line read by the shell wrapper
+            .stdout(io::stderr())
+            .status();
+
+        let error = match result {
+            Ok(status) if status.success() => continue,
+            Ok(status) => format!("{} hook failed ({}): {}", event.name(), status, label),
+            Err(e) => format!("Failed to run {} hook {}: {}", event.name(), label, e),
+        };
+        if event.is_pre() {
+            bail!(error);
+        }
+        eprintln!("Warning: {}", error);
+    }
+
+    Ok(())
+}
@@ -1,23 +1,147 @@
 mod cli;
+mod cmds;
 mod commands;
 mod config;
+mod files;
+mod forge;
 mod git;
+mod history;
+mod hooks;
+mod output;
+mod pattern;
+mod prompt;
 mod scanner;
 mod shell;
+mod trash;
 
 use clap::Parser;
 
-use cli::{Cli, Command};
+use cli::{Cli, Command, TrashCommand};
 
 fn main() {
     let cli = Cli::parse();
 
     let result = match cli.command {
-        Command::Open { branch, yes } => commands::open::run(&branch, yes),
-        Command::Close { worktree, yes } => commands::close::run(worktree.as_deref(), yes),
-        Command::List { all } => commands::list::run(all),
-        Command::Cd { worktree } => commands::cd::run(&worktree),
+        Command::Open {
+            branch,
+            yes,
+            move_changes,
+            copy_changes,
+            include_untracked,
+            pr,
+            remote,
+            fetch,
+            no_fetch,
+            push,
+        } => {
+            let carry = if move_changes {
+                Some(commands::open::CarryChanges::Move)
+            } else if copy_changes {
+                Some(commands::open::CarryChanges::Copy)
+            } else {
+                None
+            };
+            let opts = commands::open::OpenOptions {
+                yes,
+                carry,
+                include_untracked,
+                pr,
+                remote,
+                fetch: if fetch {
+                    Some(config::FetchPolicy::Always)
+                } else if no_fetch {
+                    Some(config::FetchPolicy::Never)
+                } else {
+                    None
+                },
+                push,
+            };
+            commands::open::run(branch.as_deref(), &opts)
+        }
+        Command::Close {
+            worktrees,
+            yes,
+            force,
+            stash,
+            save_patch,
+            trash,
+            all,
+            merged,
+            older_than,
+        } => {
+            let selection = commands::close::Selection {
+                names: worktrees,
+                all,
+                merged,
+                older_than,
+            };
+            let opts = commands::close::CloseOptions {
+                yes,
+                force,
+                stash,
+                save_patch,
+                trash,
+            };
+            commands::close::run(&selection, &opts)
+        }
+        Command::Restore { name } => commands::restore::run(&name),
+        Command::Trash { command } => match command {
+            TrashCommand::List => commands::trash::list(),
+            TrashCommand::Empty { all } => commands::trash::empty(all),
+        },
+        Command::Mv {
+            old,
+            new,
+            update_upstream,
+        } => commands::mv::run(&old, &new, update_upstream),
+        Command::List {
+            all,
+            include_main,
+            group,
+            repo,
+            branch,
+            dirty,
+            stale,
+        } => {
+            let filter = commands::list::WorktreeFilter {
+                branch,
+                dirty,
+                stale_days: stale,
+            };
+            commands::list::run(all, group, include_main, repo.as_deref(), &filter)
+        }
+        Command::Cd {
+            worktree,
+            recent,
+            root,
+        } => commands::cd::run(worktree.as_deref(), recent, root),
+        Command::Clone { url, dir } => commands::clone::run(&url, dir.as_deref()),
+        Command::Convert { to_normal, yes } => commands::convert::run(to_normal, yes),
+        Command::Doctor => commands::doctor::run(),
+        Command::Repair { check, yes } => commands::repair::run(check, yes),
+        Command::Status { json } => commands::status::run(json),
+        Command::Sync {
+            rebase: _,
+            merge,
+            onto,
+            dry_run,
+        } => {
+            let mode = if merge {
+                commands::sync::SyncMode::Merge
+            } else {
+                commands::sync::SyncMode::Rebase
+            };
+            commands::sync::run(mode, onto.as_deref(), dry_run)
+        }
+        Command::Exec {
+            all,
+            include_main,
+            parallel,
+            filter,
+            cmd,
+        } => commands::exec::run(all, include_main, parallel, &filter, &cmd),
         Command::Init { shell } => commands::init::run(&shell),
+        Command::Complete { target } => commands::complete::run(&target),
     };
 
     if let Err(e) = result {
@@ -0,0 +1,80 @@
+/// Width of each column: the longest of the header and every cell in it
+pub fn column_widths<R, S>(header: &[&str], rows: &[R]) -> Vec<usize>
+where
+    R: AsRef<[S]>,
+    S: AsRef<str>,
+{
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            rows.iter()
+                .map(|r| r.as_ref()[i].as_ref().len())
+                .chain([h.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Lines of a table with padded columns, header first
+pub fn render_table<R, S>(header: &[&str], rows: &[R]) -> Vec<String>
+where
+    R: AsRef<[S]>,
+    S: AsRef<str>,
+{
+    let widths = column_widths(header, rows);
+    let line = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!("{:w$}", cell, w = w))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![line(header.to_vec())];
+    for row in rows {
+        lines.push(line(row.as_ref().iter().map(AsRef::as_ref).collect()));
+    }
+    lines
+}
+
+/// Human-readable age such as "5m ago" or "3d ago"
+pub fn format_age(seconds: u64) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    if seconds < MINUTE {
+        "just now".to_string()
+    } else if seconds < HOUR {
+        format!("{}m ago", seconds / MINUTE)
+    } else if seconds < DAY {
+        format!("{}h ago", seconds / HOUR)
+    } else if seconds < 30 * DAY {
+        format!("{}d ago", seconds / DAY)
+    } else if seconds < 365 * DAY {
+        format!("{}mo ago", seconds / (30 * DAY))
+    } else {
+        format!("{}y ago", seconds / (365 * DAY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table() {
+        let rows = [["feature-a", "removed"], ["b", "failed: dirty"]];
+        assert_eq!(
+            render_table(&["WORKTREE", "RESULT"], &rows),
+            vec![
+                "WORKTREE   RESULT",
+                "feature-a  removed",
+                "b          failed: dirty",
+            ]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+/// Match text against a glob pattern supporting `*` (any run of characters) and `?` (one character)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` consume one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    // Trailing stars match the empty string
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether a name contains glob wildcards
+pub fn is_glob(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("feature-*", "feature-login"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("fix-?", "fix-1"));
+        assert!(glob_match("*/api", "frontend/api"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("fix-?", "fix-12"));
+        assert!(!glob_match("feature-*", "bugfix-login"));
+        assert!(!glob_match("a*b", "acbx"));
+    }
+
+    #[test]
+    fn test_is_glob() {
+        assert!(is_glob("fix-*"));
+        assert!(is_glob("fix-?"));
+        assert!(!is_glob("feature-login"));
+    }
+}
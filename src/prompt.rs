@@ -0,0 +1,15 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+
+/// Ask a yes/no question on stderr; anything but "y" or "yes" means no
+pub fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+
+    Ok(input == "y" || input == "yes")
+}
@@ -4,10 +4,56 @@ use std::process::Command;
 
 use anyhow::Result;
 
+use crate::config::Config;
+use crate::git::{parse_worktree_list, WorktreeInfo};
+use crate::pattern::glob_match;
+
+/// A repository found under one of the configured roots
+#[derive(Debug, Clone)]
+pub struct ScannedRepo {
+    /// Path relative to its root (host/owner/repo)
+    pub name: String,
+    /// Main worktree (the repository checkout itself)
+    pub main: Option<WorktreeInfo>,
+    /// Linked worktrees (excluding main)
+    pub worktrees: Vec<WorktreeInfo>,
+}
+
+impl ScannedRepo {
+    /// Check if a repository query matches this repository
+    /// Accepts `repo`, `owner/repo` or `host/owner/repo`
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim_matches('/');
+        !query.is_empty() && (self.name == query || self.name.ends_with(&format!("/{}", query)))
+    }
+
+    /// Check if a glob pattern matches `repo`, `owner/repo` or `host/owner/repo`
+    pub fn matches_pattern(&self, pattern: &str) -> bool {
+        let mut suffix = self.name.as_str();
+        loop {
+            if glob_match(pattern, suffix) {
+                return true;
+            }
+            match suffix.split_once('/') {
+                Some((_, rest)) => suffix = rest,
+                None => return false,
+            }
+        }
+    }
+
+    /// Short display name (owner/repo)
+    pub fn short_name(&self) -> &str {
+        match self.name.split_once('/') {
+            Some((_, rest)) if rest.contains('/') => rest,
+            _ => &self.name,
+        }
+    }
+}
+
 /// Scan all configured root directories for Git repositories and their worktrees
 /// Uses ghq-compatible structure: root/host/owner/repo
-pub fn scan_all_worktrees(roots: &[PathBuf]) -> Result<Vec<PathBuf>> {
-    let mut all_worktrees = Vec::new();
+pub fn scan_repos(roots: &[PathBuf]) -> Result<Vec<ScannedRepo>> {
+    let mut all_repos = Vec::new();
 
     for root in roots {
         if !root.exists() {
@@ -18,13 +64,26 @@ pub fn scan_all_worktrees(roots: &[PathBuf]) -> Result<Vec<PathBuf>> {
         let repos = find_repos_at_depth(root, 3);
 
         for repo_path in repos {
-            if let Ok(worktrees) = get_worktrees_for_repo(&repo_path) {
-                all_worktrees.extend(worktrees);
+            let name = repo_path
+                .strip_prefix(root)
+                .unwrap_or(&repo_path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if let Ok((main, worktrees)) = get_worktrees_for_repo(&repo_path) {
+                all_repos.push(ScannedRepo {
+                    name,
+                    main,
+                    worktrees,
+                });
             }
         }
     }
 
-    Ok(all_worktrees)
+    all_repos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(all_repos)
 }
 
 /// Find Git repositories at a specific depth
@@ -41,8 +100,8 @@ fn find_repos_recursive(
     repos: &mut Vec<PathBuf>,
 ) {
     if current_depth == target_depth {
-        // Check if this is a git repository
-        if path.join(".git").exists() {
+        // Check if this is a git repository (normal or bare)
+        if path.join(".git").exists() || is_bare_repo(path) {
             repos.push(path.to_path_buf());
         }
         return;
@@ -67,43 +126,66 @@ fn find_repos_recursive(
     }
 }
 
-/// Get worktrees for a specific repository (excluding main worktree)
-fn get_worktrees_for_repo(repo_path: &Path) -> Result<Vec<PathBuf>> {
+/// Check if a directory is itself a git directory (bare repository)
+fn is_bare_repo(path: &Path) -> bool {
+    path.join("HEAD").is_file() && path.join("objects").is_dir() && path.join("refs").is_dir()
+}
+
+/// Get the main worktree and linked worktrees for a specific repository
+/// For bare repositories the main worktree is the configured source_worktree
+fn get_worktrees_for_repo(repo_path: &Path) -> Result<(Option<WorktreeInfo>, Vec<WorktreeInfo>)> {
     let output = Command::new("git")
         .current_dir(repo_path)
         .args(["worktree", "list", "--porcelain"])
         .output()?;
 
     if !output.status.success() {
-        return Ok(Vec::new());
+        return Ok((None, Vec::new()));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut worktrees = Vec::new();
-    let mut current_path: Option<PathBuf> = None;
-    let mut is_first = true;
-
-    for line in stdout.lines() {
-        if let Some(path_str) = line.strip_prefix("worktree ") {
-            // Save previous worktree (skip main which is first)
-            if let Some(path) = current_path.take() {
-                if !is_first {
-                    worktrees.push(path);
-                }
-                is_first = false;
-            } else {
-                is_first = false;
-            }
-            current_path = Some(PathBuf::from(path_str));
-        }
-    }
+    let mut worktrees = parse_worktree_list(&stdout).into_iter();
 
-    // Don't forget the last worktree
-    if let Some(path) = current_path {
-        if !is_first {
-            worktrees.push(path);
-        }
+    // The main worktree is always listed first
+    let main = worktrees.next();
+    if !is_bare_repo(repo_path) {
+        return Ok((main, worktrees.collect()));
     }
 
-    Ok(worktrees)
+    let config = Config::load(repo_path)?;
+    let root = repo_path.canonicalize()?;
+    let (main, worktrees): (Vec<_>, Vec<_>) = worktrees.partition(|wt| {
+        config
+            .source_worktree
+            .as_ref()
+            .is_some_and(|source| wt.path == root.join(source))
+    });
+    Ok((main.into_iter().next(), worktrees))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanned_repo_matches() {
+        let repo = ScannedRepo {
+            name: "github.com/owner/repo".to_string(),
+            main: None,
+            worktrees: vec![],
+        };
+
+        assert!(repo.matches("repo"));
+        assert!(repo.matches("owner/repo"));
+        assert!(repo.matches("github.com/owner/repo"));
+        assert!(!repo.matches("epo"));
+        assert!(!repo.matches("other/repo"));
+        assert!(!repo.matches(""));
+        assert_eq!(repo.short_name(), "owner/repo");
+
+        assert!(repo.matches_pattern("repo"));
+        assert!(repo.matches_pattern("owner/*"));
+        assert!(repo.matches_pattern("github.com/*/repo"));
+        assert!(!repo.matches_pattern("other/*"));
+    }
 }
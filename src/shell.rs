@@ -26,7 +26,7 @@ gwik() {{
     local exit_code
 
     # Capture the output
-    output=$(command gwik "$@")
+    output=$(GWIK_WRAPPER=1 command gwik "$@")
     exit_code=$?
 
     if [ $exit_code -eq 0 ]; then
@@ -46,6 +46,19 @@ gwik() {{
 
 # Completions
 {completion_str}
+
+# Dynamic worktree name completion for gwik cd
+_gwik_dynamic() {{
+    if [ "$COMP_CWORD" -eq 2 ] && [ "${{COMP_WORDS[1]}}" = "cd" ]; then
+        local cur="${{COMP_WORDS[COMP_CWORD]}}"
+        # Keep the frecency order (bash 4.4+)
+        compopt -o nosort 2>/dev/null
+        COMPREPLY=($(compgen -W "$(command gwik complete cd 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    _gwik "$@"
+}}
+complete -F _gwik_dynamic -o bashdefault -o default gwik
 "#
     )
 }
@@ -64,7 +77,7 @@ gwik() {{
     local exit_code
 
     # Capture the output
-    output=$(command gwik "$@")
+    output=$(GWIK_WRAPPER=1 command gwik "$@")
     exit_code=$?
 
     if [[ $exit_code -eq 0 ]]; then
@@ -84,6 +97,19 @@ gwik() {{
 
 # Completions
 {completion_str}
+
+# Dynamic worktree name completion for gwik cd
+_gwik_dynamic() {{
+    if (( CURRENT == 3 )) && [[ "${{words[2]}}" == "cd" ]]; then
+        local -a candidates
+        candidates=(${{(f)"$(command gwik complete cd 2>/dev/null)"}})
+        # -V keeps the frecency order instead of sorting alphabetically
+        compadd -V gwik-worktrees -a candidates
+        return
+    fi
+    _gwik "$@"
+}}
+compdef _gwik_dynamic gwik
 "#
     )
 }
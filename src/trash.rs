@@ -0,0 +1,204 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::files::move_entries;
+use crate::git::{read_gitdir, GitRepo, WorktreeInfo};
+
+/// Metadata file inside each trash entry
+const META_FILE: &str = "gwik-trash.toml";
+
+/// Directory holding the worktree's files inside each trash entry
+const TREE_DIR: &str = "tree";
+
+/// What is needed to put a trashed worktree back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashMeta {
+    /// Worktree directory name
+    pub name: String,
+    pub branch: Option<String>,
+    /// Commit that was checked out
+    pub head: String,
+    /// Where the worktree was
+    pub path: PathBuf,
+    /// Repository root
+    pub repo: PathBuf,
+    /// Seconds since epoch
+    pub time: u64,
+}
+
+/// A worktree in `<git_dir>/gwik/trash/<id>`
+#[derive(Debug)]
+pub struct TrashEntry {
+    pub dir: PathBuf,
+    pub meta: TrashMeta,
+}
+
+impl TrashEntry {
+    /// Unique name of the entry (`<name>-<time>`)
+    pub fn id(&self) -> String {
+        self.dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Seconds since the worktree was trashed
+    pub fn age(&self) -> u64 {
+        now().saturating_sub(self.meta.time)
+    }
+
+    /// Ref keeping the trashed HEAD alive, since its reflog went with the worktree
+    pub fn pin_ref(&self) -> String {
+        format!("refs/gwik/trash/{}", self.id())
+    }
+
+    pub fn delete(&self, repo: &GitRepo) -> Result<()> {
+        fs::remove_dir_all(&self.dir)
+            .with_context(|| format!("Failed to delete {}", self.dir.display()))?;
+        repo.delete_ref(&self.pin_ref())
+    }
+}
+
+pub fn trash_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("gwik").join("trash")
+}
+
+/// Trashed worktrees, oldest first (unreadable entries are skipped)
+pub fn list(repo: &GitRepo) -> Result<Vec<TrashEntry>> {
+    let Ok(dirs) = fs::read_dir(trash_dir(repo)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries: Vec<TrashEntry> = dirs
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let content = fs::read_to_string(dir.join(META_FILE)).ok()?;
+            let meta = toml::from_str(&content).ok()?;
+            Some(TrashEntry { dir, meta })
+        })
+        .collect();
+    entries.sort_by_key(|e| e.meta.time);
+    Ok(entries)
+}
+
+/// Move a worktree into the trash and unregister it from git
+pub fn put(repo: &GitRepo, wt: &WorktreeInfo) -> Result<TrashEntry> {
+    // An unborn branch is listed with an all-zero HEAD
+    let Some(head) = wt.head.clone().filter(|h| h.bytes().any(|b| b != b'0')) else {
+        bail!("Worktree '{}' has no commit to restore", wt.name());
+    };
+    let time = now();
+    let mut dir = trash_dir(repo).join(format!("{}-{}", wt.name(), time));
+    for n in 2.. {
+        if !dir.exists() {
+            break;
+        }
+        dir = trash_dir(repo).join(format!("{}-{}-{}", wt.name(), time, n));
+    }
+    fs::create_dir_all(&dir)?;
+
+    let meta = TrashMeta {
+        name: wt.name(),
+        branch: wt.branch.clone(),
+        head,
+        path: wt.path.clone(),
+        repo: repo.root.clone(),
+        time,
+    };
+    let content = toml::to_string(&meta).context("Failed to serialize trash metadata")?;
+    let admin_dir = read_gitdir(&wt.path.join(".git"));
+
+    let entry = TrashEntry { dir, meta };
+    let dir = &entry.dir;
+
+    let moved = fs::write(dir.join(META_FILE), content)
+        .context("Failed to write trash metadata")
+        .and_then(|()| repo.update_ref(&entry.pin_ref(), &entry.meta.head))
+        .and_then(|()| {
+            fs::rename(&wt.path, dir.join(TREE_DIR)).with_context(|| {
+                format!(
+                    "Failed to move {} into the trash (is it on another filesystem?)",
+                    wt.path.display()
+                )
+            })
+        });
+    if let Err(e) = moved {
+        let _ = entry.delete(repo);
+        return Err(e);
+    }
+
+    // The worktree is gone from its path; drop git's record of it
+    match admin_dir {
+        Some(admin_dir) => fs::remove_dir_all(&admin_dir)?,
+        None => repo.prune_worktrees()?,
+    }
+
+    Ok(entry)
+}
+
+/// Register the worktree again and put its files back
+/// Returns the branch checked out, or None when restored at a detached HEAD
+pub fn restore(repo: &GitRepo, entry: &TrashEntry) -> Result<Option<String>> {
+    let path = &entry.meta.path;
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+
+    // The files belong on top of the trashed HEAD; if the branch moved since,
+    // checking it out would show them as a reverse diff against the new tip
+    let head = &entry.meta.head;
+    let branch = match &entry.meta.branch {
+        Some(branch) => match repo.branch_tip(branch)? {
+            Some(tip) if tip != *head => {
+                eprintln!(
+                    "Warning: Branch '{}' moved since it was trashed ({} -> {}); restoring at a detached HEAD",
+                    branch,
+                    short(head),
+                    short(&tip)
+                );
+                None
+            }
+            _ => Some(branch.clone()),
+        },
+        None => None,
+    };
+
+    repo.create_worktree_unpopulated(path, branch.as_deref(), head)?;
+    let worktree = repo
+        .list_all_worktrees()?
+        .into_iter()
+        .find(|wt| &wt.path == path)
+        .context("Restored worktree not found")?;
+
+    // The old .git file points at the removed registration; keep the new one
+    move_entries(&entry.dir.join(TREE_DIR), path, &[".git"])?;
+    worktree.reset_index()?;
+
+    entry.delete(repo)?;
+    Ok(branch)
+}
+
+/// Find the most recently trashed entry by id or worktree name
+pub fn find<'a>(entries: &'a [TrashEntry], name: &str) -> Option<&'a TrashEntry> {
+    entries
+        .iter()
+        .rev()
+        .find(|e| e.id() == name || e.meta.name == name)
+}
+
+/// Abbreviated commit hash for messages
+pub(crate) fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
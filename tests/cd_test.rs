@@ -1,6 +1,6 @@
 mod common;
 
-use common::{stderr, stdout, TestRepo};
+use common::{stderr, stdout, TestRepo, TestWorkspace};
 
 /// gwik cd <worktree-name>: Outputs a cd command to navigate to the worktree
 /// Spec: Requires exact match of worktree name
@@ -112,6 +112,41 @@ fn test_cd_with_slash_converted_name() {
     );
 }
 
+/// Branch names with slashes resolve locally instead of being treated as qualified names
+#[test]
+fn test_cd_with_branch_name() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature/auth"]);
+    repo.gwik(&["open", "fix/deep/bug"]);
+
+    let output = repo.gwik(&["cd", "feature/auth"]);
+    assert!(
+        output.status.success(),
+        "Should find worktree by branch name: {}",
+        stderr(&output)
+    );
+    assert!(stdout(&output).trim().ends_with(".worktrees/feature-auth"));
+
+    let output = repo.gwik(&["cd", "fix/deep/bug"]);
+    assert!(
+        output.status.success(),
+        "Local worktree should win over owner/repo/worktree: {}",
+        stderr(&output)
+    );
+    assert!(stdout(&output).trim().ends_with(".worktrees/fix-deep-bug"));
+
+    // An unknown name with one slash lists local worktrees
+    let output = repo.gwik(&["cd", "feature/login"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("Available worktrees") && err.contains("feature-auth"),
+        "Should list local worktrees: {}",
+        err
+    );
+}
+
 /// Test cd error message when no worktrees exist
 #[test]
 fn test_cd_no_worktrees() {
@@ -166,3 +201,286 @@ fn test_cd_is_stateless() {
     assert!(stdout(&output2).contains("wt-2"));
     assert!(stdout(&output3).contains("wt-1"));
 }
+
+/// gwik cd <repo>:<worktree>: Resolves worktrees in other repositories via roots
+#[test]
+fn test_cd_qualified_repo_colon_worktree() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "feature-x"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["cd", "repo-a:feature-x"]);
+
+    assert!(
+        output.status.success(),
+        "gwik cd repo:worktree failed: {}",
+        stderr(&output)
+    );
+    let out = stdout(&output);
+    assert!(out.starts_with("cd "), "Output should be a cd command");
+    assert!(
+        out.trim()
+            .ends_with("github.com/alice/repo-a/.worktrees/feature-x"),
+        "cd should point to the other repository's worktree: {}",
+        out
+    );
+}
+
+/// gwik cd <owner>/<repo>/<worktree>: Works from inside an unrelated repository
+#[test]
+fn test_cd_qualified_owner_repo_worktree() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    let repo_b = ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "feature-y"]);
+
+    let output = ws.gwik_in(&repo_b, &["cd", "alice/repo-a/feature-y"]);
+
+    assert!(
+        output.status.success(),
+        "gwik cd owner/repo/worktree failed: {}",
+        stderr(&output)
+    );
+    assert!(stdout(&output).contains("repo-a/.worktrees/feature-y"));
+}
+
+/// Qualified names report ambiguity when several repositories match
+#[test]
+fn test_cd_qualified_ambiguous_repo() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/tool");
+    let repo_b = ws.add_repo("github.com/bob/tool");
+
+    ws.gwik_in(&repo_a, &["open", "shared"]);
+    ws.gwik_in(&repo_b, &["open", "shared"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["cd", "tool:shared"]);
+
+    assert!(!output.status.success(), "Ambiguous name should fail");
+    let err = stderr(&output);
+    assert!(
+        err.contains("alice/tool"),
+        "Should list candidates: {}",
+        err
+    );
+    assert!(err.contains("bob/tool"), "Should list candidates: {}", err);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["cd", "bob/tool/shared"]);
+    assert!(output.status.success(), "Owner should disambiguate");
+}
+
+/// Completion candidates include qualified names for other repositories
+#[test]
+fn test_cd_completion_candidates() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+
+    ws.gwik_in(&repo_a, &["open", "feature-z"]);
+
+    let output = ws.gwik_in(&repo_a, &["complete", "cd"]);
+
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines.contains(&"feature-z"), "Local name: {}", out);
+    assert!(
+        lines.contains(&"alice/repo-a/feature-z"),
+        "Qualified name: {}",
+        out
+    );
+}
+
+/// gwik cd @: Navigates back to the main checkout
+#[test]
+fn test_cd_main_alias() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-back"]);
+
+    let output = repo.gwik_in(&repo.worktree_path("feature-back"), &["cd", "@"]);
+
+    assert!(
+        output.status.success(),
+        "gwik cd @ failed: {}",
+        stderr(&output)
+    );
+    let expected = repo.root.canonicalize().unwrap();
+    assert_eq!(stdout(&output).trim(), format!("cd {}", expected.display()));
+}
+
+/// A configured main_alias works alongside `@`
+#[test]
+fn test_cd_configured_main_alias() {
+    let repo = TestRepo::new();
+    repo.write_local_config("main_alias = \"main\"\n");
+
+    repo.gwik(&["open", "feature-alias"]);
+
+    let output = repo.gwik_in(&repo.worktree_path("feature-alias"), &["cd", "main"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let expected = repo.root.canonicalize().unwrap();
+    assert_eq!(stdout(&output).trim(), format!("cd {}", expected.display()));
+}
+
+/// For bare repositories, `@` resolves to the configured source_worktree
+#[test]
+fn test_cd_main_alias_bare_repo() {
+    let repo = TestRepo::new_bare();
+
+    let output = repo.gwik_in(&repo.root.join("main"), &["open", "feature-bare"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = repo.gwik_in(&repo.worktree_path("feature-bare"), &["cd", "@"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let expected = repo.root.canonicalize().unwrap().join("main");
+    assert_eq!(stdout(&output).trim(), format!("cd {}", expected.display()));
+}
+
+/// gwik cd -: Returns to the previously visited worktree
+#[test]
+fn test_cd_previous() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "wt-a"]);
+    repo.gwik(&["open", "wt-b"]);
+
+    let output = repo.gwik_in(&repo.worktree_path("wt-a"), &["cd", "wt-b"]);
+    assert!(output.status.success());
+
+    let output = repo.gwik_in(&repo.worktree_path("wt-b"), &["cd", "-"]);
+
+    assert!(
+        output.status.success(),
+        "gwik cd - failed: {}",
+        stderr(&output)
+    );
+    assert!(
+        stdout(&output).trim().ends_with(".worktrees/wt-a"),
+        "Should go back to wt-a: {}",
+        stdout(&output)
+    );
+}
+
+/// gwik cd -: Fails cleanly when there is no history
+#[test]
+fn test_cd_previous_without_history() {
+    let repo = TestRepo::new();
+
+    let output = repo.gwik(&["cd", "-"]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No previous worktree"));
+}
+
+/// gwik cd --recent: Lists visited worktrees ranked by frecency
+#[test]
+fn test_cd_recent_ranked_by_frecency() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "rarely"]);
+    repo.gwik(&["open", "often"]);
+    for _ in 0..3 {
+        repo.gwik(&["cd", "often"]);
+    }
+    repo.gwik(&["cd", "rarely"]);
+
+    let output = repo.gwik(&["cd", "--recent"]);
+
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].ends_with("often"), "Most visited first: {}", out);
+    assert!(lines.iter().any(|l| l.ends_with("rarely")), "{}", out);
+}
+
+/// Completion candidates follow frecency and offer `@` for the main checkout
+#[test]
+fn test_cd_completion_ranked_by_frecency() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "alpha"]);
+    repo.gwik(&["open", "zulu"]);
+    for _ in 0..3 {
+        repo.gwik(&["cd", "zulu"]);
+    }
+
+    let output = repo.gwik(&["complete", "cd"]);
+
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "zulu", "Most visited first: {}", out);
+    assert!(lines.contains(&"@"), "Main alias: {}", out);
+    assert!(lines.contains(&"alpha"), "{}", out);
+}
+
+/// Switching worktrees keeps the current subdirectory when it exists in the target
+#[test]
+fn test_cd_preserves_subdirectory() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.root.join("services/api")).unwrap();
+    std::fs::write(repo.root.join("services/api/main.rs"), "fn main() {}\n").unwrap();
+    repo.git(&["add", "."]);
+    repo.git(&["commit", "-m", "Add api"]);
+
+    repo.gwik(&["open", "feat-a"]);
+    repo.gwik(&["open", "feat-b"]);
+
+    let subdir = repo.worktree_path("feat-a").join("services/api");
+    let output = repo.gwik_in(&subdir, &["cd", "feat-b"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output)
+            .trim()
+            .ends_with(".worktrees/feat-b/services/api"),
+        "Should land in the same subdirectory: {}",
+        stdout(&output)
+    );
+
+    // --root opts out
+    let output = repo.gwik_in(&subdir, &["cd", "--root", "feat-b"]);
+    assert!(stdout(&output).trim().ends_with(".worktrees/feat-b"));
+}
+
+/// Falls back to the worktree root when the subdirectory does not exist in the target
+#[test]
+fn test_cd_subdirectory_fallback_to_root() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feat-a"]);
+    repo.gwik(&["open", "feat-b"]);
+
+    let subdir = repo.worktree_path("feat-a").join("only-here");
+    std::fs::create_dir_all(&subdir).unwrap();
+
+    let output = repo.gwik_in(&subdir, &["cd", "feat-b"]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).trim().ends_with(".worktrees/feat-b"));
+}
+
+/// keep_subdir = false makes landing at the root the default
+#[test]
+fn test_cd_keep_subdir_config() {
+    let repo = TestRepo::new();
+    repo.write_local_config("keep_subdir = false\n");
+
+    repo.gwik(&["open", "feat-a"]);
+    repo.gwik(&["open", "feat-b"]);
+    std::fs::create_dir_all(repo.worktree_path("feat-a").join("docs")).unwrap();
+    std::fs::create_dir_all(repo.worktree_path("feat-b").join("docs")).unwrap();
+
+    let output = repo.gwik_in(
+        &repo.worktree_path("feat-a").join("docs"),
+        &["cd", "feat-b"],
+    );
+
+    assert!(output.status.success());
+    assert!(stdout(&output).trim().ends_with(".worktrees/feat-b"));
+}
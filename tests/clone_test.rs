@@ -0,0 +1,110 @@
+mod common;
+
+use common::{stderr, stdout, TestWorkspace};
+
+/// gwik clone <url> <dir>: Creates a bare repository with a source worktree
+#[test]
+fn test_clone_into_dir() {
+    let ws = TestWorkspace::new();
+    let url = ws.add_upstream("owner/repo");
+    let outside = ws.outside_dir();
+
+    let output = ws.gwik_in(&outside, &["clone", &url, "repo"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let root = outside.join("repo");
+    let source = root.join("main");
+    assert_eq!(
+        stdout(&output).trim(),
+        format!("cd {}", source.canonicalize().unwrap().display())
+    );
+    assert!(root.join("HEAD").is_file(), "Should be a bare repository");
+    assert_eq!(
+        std::fs::read_to_string(root.join("gwik.toml")).unwrap(),
+        "source_worktree = \"main\"\n"
+    );
+
+    // Remote-tracking refs and upstream are set up like a normal clone
+    let upstream = stdout(&ws.git_in(&source, &["rev-parse", "--abbrev-ref", "@{u}"]));
+    assert_eq!(upstream.trim(), "origin/main");
+    let base = stdout(&ws.git_in(
+        &root,
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+    ));
+    assert_eq!(base.trim(), "origin/main");
+}
+
+/// Without a directory, clones under the first root as host/owner/repo
+#[test]
+fn test_clone_into_root() {
+    let ws = TestWorkspace::new();
+    let url = ws.add_upstream("owner/repo");
+    let outside = ws.outside_dir();
+
+    let output = ws.gwik_in(&outside, &["clone", &url]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(ws.root.join("localhost/owner/repo/main").is_dir());
+
+    // The bare repository is found by the scanner, with the source worktree as main
+    let output = ws.gwik_in(&outside, &["cd", "repo:@"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output)
+            .trim()
+            .ends_with("localhost/owner/repo/main"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+/// Worktrees can be opened from the clone right away
+#[test]
+fn test_clone_then_open() {
+    let ws = TestWorkspace::new();
+    let url = ws.add_upstream("owner/repo");
+    let outside = ws.outside_dir();
+
+    ws.gwik_in(&outside, &["clone", &url, "repo"]);
+
+    let source = outside.join("repo").join("main");
+    let output = ws.gwik_in(&source, &["open", "feature"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(outside.join("repo/.worktrees/feature").is_dir());
+}
+
+/// Fails without a directory when no roots are configured
+#[test]
+fn test_clone_requires_dir_or_roots() {
+    let ws = TestWorkspace::new();
+    let url = ws.add_upstream("owner/repo");
+    std::fs::remove_file(ws.env_dir.path().join("config/gwik/config.toml")).unwrap();
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["clone", &url]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No roots configured"));
+}
+
+/// A clone that fails after the bare clone leaves nothing behind to block a retry
+#[test]
+fn test_clone_cleans_up_on_failure() {
+    let ws = TestWorkspace::new();
+    let outside = ws.outside_dir();
+    // An empty repository has no default branch to check out
+    ws.git_in(&outside, &["init", "--bare", "empty.git"]);
+    let url = format!("file://{}", outside.join("empty.git").display());
+
+    let output = ws.gwik_in(&outside, &["clone", &url, "repo"]);
+    assert!(!output.status.success());
+    assert!(
+        !outside.join("repo").exists(),
+        "Half-configured clone should be removed: {}",
+        stderr(&output)
+    );
+
+    // An existing empty destination is kept, but emptied again
+    std::fs::create_dir(outside.join("kept")).unwrap();
+    let output = ws.gwik_in(&outside, &["clone", &url, "kept"]);
+    assert!(!output.status.success());
+    assert!(outside.join("kept").is_dir());
+    assert_eq!(std::fs::read_dir(outside.join("kept")).unwrap().count(), 0);
+}
@@ -242,3 +242,424 @@ fn test_close_nonexistent_no_worktrees() {
         "Should indicate worktree not found"
     );
 }
+
+/// Hook files in .git/gwik/hooks run on close; a failing pre-close hook keeps the worktree
+#[test]
+fn test_close_hooks() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-hooks"]);
+
+    let hooks_dir = repo.root.join(".git/gwik/hooks");
+    fs::create_dir_all(&hooks_dir).unwrap();
+    let write_hook = |name: &str, script: &str| {
+        let path = hooks_dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    write_hook("pre-close", "test ! -e \"$GWIK_DST/keep\"");
+    write_hook(
+        "post-close",
+        "echo \"$GWIK_EVENT $GWIK_BRANCH $(pwd)\" > \"$GWIK_SRC/closed.log\"",
+    );
+
+    repo.create_file_in_worktree("feature-hooks", "keep", "");
+    repo.git_in(&repo.worktree_path("feature-hooks"), &["add", "keep"]);
+    repo.git_in(
+        &repo.worktree_path("feature-hooks"),
+        &["commit", "-q", "-m", "keep"],
+    );
+    let output = repo.gwik(&["close", "feature-hooks", "--force"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("pre-close hook failed"));
+    assert!(repo.worktree_exists("feature-hooks"));
+
+    repo.git_in(&repo.worktree_path("feature-hooks"), &["rm", "-q", "keep"]);
+    repo.git_in(
+        &repo.worktree_path("feature-hooks"),
+        &["commit", "-q", "-m", "drop"],
+    );
+    let output = repo.gwik(&["close", "feature-hooks", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let log = fs::read_to_string(repo.root.join("closed.log")).unwrap();
+    assert_eq!(
+        log.trim(),
+        format!("post-close feature-hooks {}", repo.root.display())
+    );
+}
+
+/// Commits that exist only on the branch block close until --force; pushing them unblocks it
+#[test]
+fn test_close_unpushed_commits_require_force() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.gwik(&["open", "feature-unpushed"]);
+    let worktree = repo.worktree_path("feature-unpushed");
+    repo.commit_file_in(&worktree, "work.txt", "work\n");
+
+    let output = repo.gwik(&["close", "feature-unpushed"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("Branch 'feature-unpushed' has 1 commit(s) not on any remote"),
+        "{}",
+        err
+    );
+    assert!(err.contains("--force"), "{}", err);
+    assert!(repo.worktree_exists("feature-unpushed"));
+
+    repo.git_in(&worktree, &["push", "-q", "origin", "feature-unpushed"]);
+    let output = repo.gwik(&["close", "feature-unpushed"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+/// Another local branch at the same commit does not make the work pushed
+#[test]
+fn test_close_unpushed_commits_on_other_branch() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.gwik(&["open", "feature-copied"]);
+    repo.commit_file_in(&repo.worktree_path("feature-copied"), "work.txt", "work\n");
+    repo.git(&["branch", "feature-copy", "feature-copied"]);
+
+    let output = repo.gwik(&["close", "feature-copied"]);
+    assert!(!output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("has 1 commit(s) not on any remote"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+/// --force closes despite unpushed commits
+#[test]
+fn test_close_force_skips_safety_checks() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.gwik(&["open", "feature-forced"]);
+    repo.commit_file_in(&repo.worktree_path("feature-forced"), "work.txt", "work\n");
+
+    let output = repo.gwik(&["close", "feature-forced", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo.worktree_exists("feature-forced"));
+}
+
+/// Stashes made on the branch block close
+#[test]
+fn test_close_stashes_require_force() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-stash"]);
+    let worktree = repo.worktree_path("feature-stash");
+    std::fs::write(worktree.join("README.md"), "changed\n").unwrap();
+    repo.git_in(&worktree, &["stash", "-q"]);
+
+    let output = repo.gwik(&["close", "feature-stash"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("1 stash(es) were made on branch 'feature-stash'"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+/// A detached HEAD at a commit no branch contains blocks close
+#[test]
+fn test_close_unreachable_detached_head() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-detached"]);
+    let worktree = repo.worktree_path("feature-detached");
+    repo.git_in(&worktree, &["checkout", "-q", "--detach"]);
+
+    // Detached at a commit that is on a branch: fine
+    let output = repo.gwik(&["close", "feature-detached"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    repo.gwik(&["open", "feature-orphan"]);
+    let worktree = repo.worktree_path("feature-orphan");
+    repo.git_in(&worktree, &["checkout", "-q", "--detach"]);
+    repo.commit_file_in(&worktree, "lost.txt", "lost\n");
+
+    let output = repo.gwik(&["close", "feature-orphan"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("which is not reachable from any branch"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+/// --force lists the changes that will be lost and asks first; --yes skips the question
+#[test]
+fn test_close_force_discards_changes() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-discard"]);
+    repo.create_file_in_worktree("feature-discard", "scratch.txt", "scratch");
+
+    // No answer on stdin declines
+    let output = repo.gwik(&["close", "feature-discard", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let err = stderr(&output);
+    assert!(err.contains("These changes will be lost"), "{}", err);
+    assert!(err.contains("?? scratch.txt"), "{}", err);
+    assert!(err.contains("Aborted."), "{}", err);
+    assert!(repo.worktree_exists("feature-discard"));
+
+    let output = repo.gwik(&["close", "feature-discard", "--force", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo.worktree_exists("feature-discard"));
+}
+
+/// The --force confirmation also lists the commits that will be lost
+#[test]
+fn test_close_force_lists_unpushed_commits() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.gwik(&["open", "feature-both"]);
+    repo.commit_file_in(&repo.worktree_path("feature-both"), "work.txt", "work\n");
+    repo.create_file_in_worktree("feature-both", "scratch.txt", "scratch");
+
+    let output = repo.gwik(&["close", "feature-both", "--force"]);
+    let err = stderr(&output);
+    assert!(err.contains("1 commit(s) not on any remote"), "{}", err);
+    assert!(err.contains("?? scratch.txt"), "{}", err);
+    assert!(err.contains("Aborted."), "{}", err);
+    assert!(repo.worktree_exists("feature-both"));
+}
+
+/// --stash keeps the changes, untracked files included, as a stash on the branch
+#[test]
+fn test_close_stash() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-keep"]);
+    let worktree = repo.worktree_path("feature-keep");
+    std::fs::write(worktree.join("README.md"), "edited\n").unwrap();
+    repo.create_file_in_worktree("feature-keep", "notes.txt", "notes");
+
+    let output = repo.gwik(&["close", "feature-keep", "--stash"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Stashed changes as"));
+    assert!(!repo.worktree_exists("feature-keep"));
+
+    let stashes = stdout(&repo.git(&["stash", "list", "--format=%gs"]));
+    assert_eq!(stashes.trim(), "On feature-keep: gwik close feature-keep");
+
+    repo.gwik(&["open", "feature-keep", "--yes"]);
+    repo.git_in(&worktree, &["stash", "pop", "-q"]);
+    assert_eq!(
+        std::fs::read_to_string(worktree.join("README.md")).unwrap(),
+        "edited\n"
+    );
+    assert!(worktree.join("notes.txt").exists());
+}
+
+/// --save-patch writes the changes under .git/gwik/archive and never overwrites
+#[test]
+fn test_close_save_patch() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-patch"]);
+    let worktree = repo.worktree_path("feature-patch");
+    std::fs::write(worktree.join("README.md"), "patched\n").unwrap();
+    repo.create_file_in_worktree("feature-patch", "added.txt", "added\n");
+
+    let output = repo.gwik(&["close", "feature-patch", "--save-patch", "work.patch"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo.worktree_exists("feature-patch"));
+
+    let patch = repo.root.join(".git/gwik/archive/work.patch");
+    let output = repo.git(&["apply", patch.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(repo.root.join("README.md")).unwrap(),
+        "patched\n"
+    );
+    assert!(repo.root.join("added.txt").exists());
+
+    repo.gwik(&["open", "feature-again"]);
+    repo.create_file_in_worktree("feature-again", "more.txt", "more");
+    let output = repo.gwik(&["close", "feature-again", "--save-patch", "work.patch"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already exists"));
+    assert!(repo.worktree_exists("feature-again"));
+}
+
+/// --save-patch works on an unborn branch and refuses to leave the archive dir
+#[test]
+fn test_close_save_patch_edge_cases() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-escape"]);
+    repo.create_file_in_worktree("feature-escape", "x.txt", "x");
+    let output = repo.gwik(&["close", "feature-escape", "--save-patch", "../../x.patch"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("cannot contain '..'"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo.worktree_exists("feature-escape"));
+
+    repo.gwik(&["open", "feature-unborn"]);
+    let worktree = repo.worktree_path("feature-unborn");
+    repo.git_in(&worktree, &["checkout", "-q", "--orphan", "fresh"]);
+    repo.create_file_in_worktree("feature-unborn", "first.txt", "first\n");
+    let output = repo.gwik(&["close", "feature-unborn", "--save-patch", "unborn.patch"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let patch = std::fs::read_to_string(repo.root.join(".git/gwik/archive/unborn.patch")).unwrap();
+    assert!(patch.contains("+first"), "{}", patch);
+}
+
+/// Several names and globs close every match after one confirmation
+#[test]
+fn test_close_many_with_globs() {
+    let repo = TestRepo::new();
+    for name in ["fix-a", "fix-b", "feature-c", "other"] {
+        repo.gwik(&["open", name]);
+    }
+
+    // No answer on stdin declines, after showing what would be closed
+    let output = repo.gwik(&["close", "fix-*", "feature-c"]);
+    let err = stderr(&output);
+    assert!(err.contains("WORKTREE"), "{}", err);
+    assert!(err.contains("Close 3 worktree(s)?"), "{}", err);
+    assert!(err.contains("Aborted."), "{}", err);
+    assert!(repo.worktree_exists("fix-a"));
+
+    let output = repo.gwik(&["close", "fix-*", "feature-c", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo.worktree_exists("fix-a"));
+    assert!(!repo.worktree_exists("fix-b"));
+    assert!(!repo.worktree_exists("feature-c"));
+    assert!(repo.worktree_exists("other"));
+    assert!(
+        stdout(&output).is_empty(),
+        "No cd when the current directory survives"
+    );
+}
+
+/// A literal name that matches nothing fails before anything is closed
+#[test]
+fn test_close_many_unknown_name() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "fix-a"]);
+    repo.gwik(&["open", "other"]);
+
+    let output = repo.gwik(&["close", "fix-a", "otehr", "--yes"]);
+    assert!(
+        !output.status.success(),
+        "A typo must not look like success"
+    );
+    let err = stderr(&output);
+    assert!(err.contains("Worktree 'otehr' not found"), "{}", err);
+    assert!(err.contains("Available worktrees"), "{}", err);
+    assert!(repo.worktree_exists("fix-a"));
+
+    // A glob that matches nothing only warns
+    let output = repo.gwik(&["close", "fix-a", "nope-*", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("No worktree matches 'nope-*'"));
+    assert!(!repo.worktree_exists("fix-a"));
+}
+
+/// A failure is reported per worktree and does not stop the others
+#[test]
+fn test_close_many_reports_failures() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "batch-clean"]);
+    repo.gwik(&["open", "batch-dirty"]);
+    repo.gwik(&["open", "batch-later"]);
+    repo.create_file_in_worktree("batch-dirty", "wip.txt", "wip");
+
+    let output = repo.gwik(&["close", "--all", "--yes"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("batch-dirty  failed: Worktree has uncommitted changes"),
+        "{}",
+        err
+    );
+    assert!(err.contains("batch-later  removed"), "{}", err);
+    assert!(
+        err.contains("1 of 3 worktrees could not be closed"),
+        "{}",
+        err
+    );
+    assert!(repo.worktree_exists("batch-dirty"));
+    assert!(!repo.worktree_exists("batch-clean"));
+    assert!(!repo.worktree_exists("batch-later"));
+}
+
+/// Leaving a removed current directory exits 0 so the wrapper can cd, even if others failed
+#[test]
+fn test_close_many_from_inside_with_failures() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "batch-here"]);
+    repo.gwik(&["open", "batch-dirty"]);
+    repo.create_file_in_worktree("batch-dirty", "wip.txt", "wip");
+
+    let output = repo.gwik_in(
+        &repo.worktree_path("batch-here"),
+        &["close", "--all", "--yes"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let err = stderr(&output);
+    assert!(err.contains("batch-dirty  failed"), "{}", err);
+    assert!(
+        err.contains("Warning: 1 of 2 worktrees could not be closed"),
+        "{}",
+        err
+    );
+    assert!(stdout(&output).starts_with("cd "), "{}", stdout(&output));
+    assert!(!repo.worktree_exists("batch-here"));
+    assert!(repo.worktree_exists("batch-dirty"));
+}
+
+/// With --force the confirmation lists every file and commit that will be lost
+#[test]
+fn test_close_many_force_lists_losses() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.gwik(&["open", "batch-files"]);
+    repo.gwik(&["open", "batch-commits"]);
+    repo.create_file_in_worktree("batch-files", "scratch.txt", "scratch");
+    repo.commit_file_in(&repo.worktree_path("batch-commits"), "work.txt", "work\n");
+
+    // No answer on stdin declines
+    let output = repo.gwik(&["close", "--all", "--force"]);
+    let err = stderr(&output);
+    assert!(err.contains("This work will be lost"), "{}", err);
+    assert!(err.contains("batch-files: ?? scratch.txt"), "{}", err);
+    assert!(
+        err.contains("batch-commits: Branch 'batch-commits' has 1 commit(s) not on any remote"),
+        "{}",
+        err
+    );
+    assert!(err.contains("Aborted."), "{}", err);
+    assert!(repo.worktree_exists("batch-files"));
+    assert!(repo.worktree_exists("batch-commits"));
+}
+
+/// --merged and --older-than select worktrees by branch state and age
+#[test]
+fn test_close_selectors() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "merged-one"]);
+    repo.gwik(&["open", "unmerged-one"]);
+    repo.commit_file_in(&repo.worktree_path("merged-one"), "m.txt", "m\n");
+    repo.git(&["merge", "-q", "--ff-only", "merged-one"]);
+    repo.commit_file_in(&repo.worktree_path("unmerged-one"), "u.txt", "u\n");
+
+    let output = repo.gwik(&["close", "--merged", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo.worktree_exists("merged-one"));
+    assert!(repo.worktree_exists("unmerged-one"));
+
+    let output = repo.gwik(&["close", "--older-than", "30d", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("No worktrees to close."));
+    assert!(repo.worktree_exists("unmerged-one"));
+
+    let output = repo.gwik(&["close", "--older-than", "soon"]);
+    assert!(!output.status.success());
+}
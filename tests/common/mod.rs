@@ -10,6 +10,8 @@ use tempfile::TempDir;
 pub struct TestRepo {
     pub temp_dir: TempDir,
     pub root: PathBuf,
+    /// Isolated XDG directories (config, state, data) for gwik
+    pub env_dir: TempDir,
 }
 
 impl TestRepo {
@@ -17,22 +19,38 @@ impl TestRepo {
     pub fn new() -> Self {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
         let root = temp_dir.path().to_path_buf();
+        let env_dir = TempDir::new().expect("Failed to create env dir");
 
-        // Initialize git repository
-        run_git(&root, &["init"]).expect("git init failed");
+        init_repo(&root);
 
-        // Configure git user for commits
-        run_git(&root, &["config", "user.email", "test@test.com"])
-            .expect("git config email failed");
-        run_git(&root, &["config", "user.name", "Test User"]).expect("git config name failed");
+        Self {
+            temp_dir,
+            root,
+            env_dir,
+        }
+    }
 
-        // Create initial commit
-        let readme = root.join("README.md");
-        fs::write(&readme, "# Test Repository\n").expect("Failed to write README");
-        run_git(&root, &["add", "."]).expect("git add failed");
-        run_git(&root, &["commit", "-m", "Initial commit"]).expect("git commit failed");
+    /// Create a bare repository with a `main` source worktree configured in gwik.toml
+    pub fn new_bare() -> Self {
+        let seed = Self::new();
+        seed.git(&["branch", "-M", "main"]);
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path().join("repo.git");
+        run_git(
+            temp_dir.path(),
+            &["clone", "--bare", seed.root.to_str().unwrap(), "repo.git"],
+        )
+        .expect("git clone --bare failed");
+        run_git(&root, &["worktree", "add", "main", "main"]).expect("git worktree add failed");
+        fs::write(root.join("gwik.toml"), "source_worktree = \"main\"\n")
+            .expect("Failed to write local config");
 
-        Self { temp_dir, root }
+        Self {
+            temp_dir,
+            root,
+            env_dir: seed.env_dir,
+        }
     }
 
     /// Get the gwik binary path
@@ -59,16 +77,12 @@ impl TestRepo {
 
     /// Run gwik command in this repository
     pub fn gwik(&self, args: &[&str]) -> Output {
-        Command::new(Self::gwik_bin())
-            .args(args)
-            .current_dir(&self.root)
-            .output()
-            .expect("Failed to run gwik")
+        self.gwik_in(&self.root, args)
     }
 
     /// Run gwik command in a specific directory
     pub fn gwik_in(&self, dir: &Path, args: &[&str]) -> Output {
-        Command::new(Self::gwik_bin())
+        gwik_command(self.env_dir.path())
             .args(args)
             .current_dir(dir)
             .output()
@@ -80,6 +94,30 @@ impl TestRepo {
         run_git(&self.root, args).expect("git command failed")
     }
 
+    /// Run git command in a specific directory (e.g. a worktree)
+    pub fn git_in(&self, dir: &Path, args: &[&str]) -> Output {
+        let output = run_git(dir, args).expect("git command failed");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    }
+
+    /// Write a file and commit it in a specific directory (e.g. a worktree)
+    pub fn commit_file_in(&self, dir: &Path, file: &str, content: &str) {
+        fs::write(dir.join(file), content).expect("Failed to write file");
+        self.git_in(dir, &["add", file]);
+        self.git_in(dir, &["commit", "-m", &format!("Update {}", file)]);
+    }
+
+    /// Get the commit id of a revision
+    pub fn rev_parse(&self, rev: &str) -> String {
+        stdout(&self.git(&["rev-parse", rev])).trim().to_string()
+    }
+
     /// Create a branch at current HEAD
     pub fn create_branch(&self, name: &str) {
         self.git(&["branch", name]);
@@ -88,7 +126,10 @@ impl TestRepo {
     /// Create a remote and push a branch to it (without leaving local branch)
     pub fn create_remote_branch(&self, remote: &str, branch: &str) {
         // Create a bare remote repository
-        let remote_dir = self.temp_dir.path().join("remote");
+        let remote_dir = self
+            .temp_dir
+            .path()
+            .join(format!("remote-{}", remote.replace('/', "-")));
         fs::create_dir_all(&remote_dir).expect("Failed to create remote dir");
         run_git(&remote_dir, &["init", "--bare"]).expect("git init --bare failed");
 
@@ -104,6 +145,19 @@ impl TestRepo {
         self.git(&["fetch", remote]);
     }
 
+    /// Push a new commit (on top of `base`) to an arbitrary ref on a remote, like a forge's pull ref
+    pub fn push_fake_ref(&self, remote: &str, base: &str, refname: &str, file: &str) -> String {
+        self.git_in(&self.root, &["checkout", "-q", "--detach", base]);
+        self.commit_file_in(&self.root, file, "pull request\n");
+        let sha = self.rev_parse("HEAD");
+        self.git_in(
+            &self.root,
+            &["push", "-q", remote, &format!("HEAD:{}", refname)],
+        );
+        self.git_in(&self.root, &["checkout", "-q", "-"]);
+        sha
+    }
+
     /// Get path to a worktree
     pub fn worktree_path(&self, name: &str) -> PathBuf {
         self.root.join(".worktrees").join(name)
@@ -128,9 +182,120 @@ impl TestRepo {
 
     /// Write local gwik config
     pub fn write_local_config(&self, content: &str) {
-        let config_path = self.root.join(".git").join("gwik.toml");
+        // Bare repositories keep gwik.toml in the repository directory itself
+        let git_dir = self.root.join(".git");
+        let git_dir = if git_dir.is_dir() {
+            git_dir
+        } else {
+            self.root.clone()
+        };
+        let config_path = git_dir.join("gwik.toml");
         fs::write(&config_path, content).expect("Failed to write local config");
     }
+
+    /// Write global gwik config (in the isolated config directory)
+    pub fn write_global_config(&self, content: &str) {
+        write_global_config(self.env_dir.path(), content);
+    }
+}
+
+/// Test fixture that provides a ghq-style root (host/owner/repo) with multiple repositories
+pub struct TestWorkspace {
+    pub temp_dir: TempDir,
+    /// Root directory registered in `roots`
+    pub root: PathBuf,
+    /// Isolated XDG directories (config, state, data) for gwik
+    pub env_dir: TempDir,
+}
+
+impl TestWorkspace {
+    /// Create an empty workspace whose root is registered in the global config
+    pub fn new() -> Self {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(&root).expect("Failed to create root dir");
+        let env_dir = TempDir::new().expect("Failed to create env dir");
+
+        write_global_config(
+            env_dir.path(),
+            &format!("roots = [\"{}\"]\n", root.display()),
+        );
+
+        Self {
+            temp_dir,
+            root,
+            env_dir,
+        }
+    }
+
+    /// Create a repository at root/<name> (e.g. "github.com/owner/repo")
+    pub fn add_repo(&self, name: &str) -> PathBuf {
+        let path = self.root.join(name);
+        fs::create_dir_all(&path).expect("Failed to create repo dir");
+        init_repo(&path);
+        path
+    }
+
+    /// Create a repository outside the root to clone from, returning its file:// URL
+    pub fn add_upstream(&self, name: &str) -> String {
+        let path = self.temp_dir.path().join("upstream").join(name);
+        fs::create_dir_all(&path).expect("Failed to create upstream dir");
+        init_repo(&path);
+        run_git(&path, &["branch", "-M", "main"]).expect("git branch -M failed");
+        format!("file://{}", path.display())
+    }
+
+    /// A directory outside of any git repository
+    pub fn outside_dir(&self) -> PathBuf {
+        let path = self.temp_dir.path().join("outside");
+        fs::create_dir_all(&path).expect("Failed to create outside dir");
+        path
+    }
+
+    /// Run gwik command in a specific directory
+    pub fn gwik_in(&self, dir: &Path, args: &[&str]) -> Output {
+        gwik_command(self.env_dir.path())
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run gwik")
+    }
+
+    /// Run git command in a specific directory
+    pub fn git_in(&self, dir: &Path, args: &[&str]) -> Output {
+        run_git(dir, args).expect("git command failed")
+    }
+}
+
+/// Initialize a git repository with an initial commit
+fn init_repo(root: &Path) {
+    run_git(root, &["init"]).expect("git init failed");
+
+    // Configure git user for commits
+    run_git(root, &["config", "user.email", "test@test.com"]).expect("git config email failed");
+    run_git(root, &["config", "user.name", "Test User"]).expect("git config name failed");
+
+    // Create initial commit
+    let readme = root.join("README.md");
+    fs::write(&readme, "# Test Repository\n").expect("Failed to write README");
+    run_git(root, &["add", "."]).expect("git add failed");
+    run_git(root, &["commit", "-m", "Initial commit"]).expect("git commit failed");
+}
+
+/// Build a gwik command with XDG directories pointed at `env_dir`
+pub fn gwik_command(env_dir: &Path) -> Command {
+    let mut cmd = Command::new(TestRepo::gwik_bin());
+    cmd.env("XDG_CONFIG_HOME", env_dir.join("config"))
+        .env("XDG_STATE_HOME", env_dir.join("state"))
+        .env("XDG_DATA_HOME", env_dir.join("data"))
+        .env_remove("GWIK_WRAPPER");
+    cmd
+}
+
+fn write_global_config(env_dir: &Path, content: &str) {
+    let config_dir = env_dir.join("config").join("gwik");
+    fs::create_dir_all(&config_dir).expect("Failed to create config dir");
+    fs::write(config_dir.join("config.toml"), content).expect("Failed to write global config");
 }
 
 fn run_git(dir: &Path, args: &[&str]) -> Result<Output, std::io::Error> {
@@ -0,0 +1,148 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestRepo};
+
+/// Changes of every kind in the main checkout
+fn make_changes(repo: &TestRepo, dir: &std::path::Path) {
+    fs::write(dir.join("README.md"), "modified\n").unwrap();
+    fs::write(dir.join("staged.txt"), "staged\n").unwrap();
+    repo.git_in(dir, &["add", "staged.txt"]);
+    fs::write(dir.join("untracked.txt"), "untracked\n").unwrap();
+}
+
+/// gwik convert: Moves a normal clone to the bare layout, keeping changes and worktrees
+#[test]
+fn test_convert_to_bare() {
+    let repo = TestRepo::new();
+    repo.git(&["branch", "-M", "main"]);
+    repo.gwik(&["open", "feature"]);
+    make_changes(&repo, &repo.root);
+    let status_before = stdout(&repo.git(&["status", "--porcelain"]));
+
+    let output = repo.gwik(&["convert", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let source = repo.root.join("main");
+    assert!(
+        repo.root.join("HEAD").is_file(),
+        "Root should be the git dir"
+    );
+    assert!(!repo.root.join(".git").exists());
+    assert!(
+        stdout(&output).trim().ends_with("/main"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        fs::read_to_string(repo.root.join("gwik.toml")).unwrap(),
+        "source_worktree = \"main\"\n"
+    );
+
+    // Staged, unstaged and untracked changes survive
+    let status_after = stdout(&repo.git_in(&source, &["status", "--porcelain"]));
+    assert_eq!(status_after, status_before);
+
+    // Existing worktrees keep working, and gwik sees the new layout
+    repo.git_in(&repo.worktree_path("feature"), &["status"]);
+    let output = repo.gwik_in(&repo.worktree_path("feature"), &["cd", "@"]);
+    assert_eq!(stdout(&output).trim(), format!("cd {}", source.display()));
+}
+
+/// --to-normal restores the original layout
+#[test]
+fn test_convert_round_trip() {
+    let repo = TestRepo::new();
+    repo.git(&["branch", "-M", "main"]);
+    repo.gwik(&["open", "feature"]);
+    repo.write_local_config("keep_subdir = false\n");
+    make_changes(&repo, &repo.root);
+    let status_before = stdout(&repo.git(&["status", "--porcelain"]));
+
+    let output = repo.gwik(&["convert", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = repo.gwik_in(
+        &repo.root.join("main"),
+        &["convert", "--to-normal", "--yes"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    assert!(repo.root.join(".git").is_dir());
+    assert!(!repo.root.join("main").exists());
+    assert_eq!(stdout(&repo.git(&["status", "--porcelain"])), status_before);
+    assert_eq!(
+        fs::read_to_string(repo.root.join(".git/gwik.toml")).unwrap(),
+        "keep_subdir = false\n"
+    );
+    repo.git_in(&repo.worktree_path("feature"), &["status"]);
+    let bare = stdout(&repo.git(&["config", "core.bare"]));
+    assert_eq!(bare.trim(), "false");
+}
+
+/// Refuses when worktrees live inside .git
+#[test]
+fn test_convert_refuses_worktree_dir_in_git() {
+    let repo = TestRepo::new();
+    repo.write_local_config("worktree_dir = \".git/.worktrees\"\n");
+
+    let output = repo.gwik(&["convert", "--yes"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("inside .git"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo.root.join(".git").is_dir());
+}
+
+/// Refuses on detached HEAD or when the repository is already bare
+#[test]
+fn test_convert_refuses_unsafe_states() {
+    let repo = TestRepo::new();
+    repo.git(&["checkout", "--detach"]);
+
+    let output = repo.gwik(&["convert", "--yes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("detached HEAD"));
+
+    let bare = TestRepo::new_bare();
+    let output = bare.gwik(&["convert", "--yes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already bare"));
+}
+
+/// A failure after the checkout was moved aside puts everything back
+#[test]
+fn test_convert_rolls_back_on_failure() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature"]);
+    make_changes(&repo, &repo.root);
+    let status_before = stdout(&repo.git(&["status", "--porcelain"]));
+    // Clashes with the worktree directory once .git's entries move to the root
+    fs::create_dir(repo.root.join(".git").join(".worktrees")).unwrap();
+
+    let output = repo.gwik(&["convert", "--yes"]);
+
+    assert!(!output.status.success(), "Conversion should fail");
+    assert!(
+        !stderr(&output).contains("Could not undo"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!repo.root.join(".gwik-convert").exists());
+    assert!(repo.root.join(".git").join("HEAD").is_file());
+    assert_eq!(
+        fs::read_to_string(repo.root.join("README.md")).unwrap(),
+        "modified\n"
+    );
+    assert_eq!(
+        stdout(&repo.git(&["status", "--porcelain"])),
+        status_before,
+        "Changes should be intact"
+    );
+    assert_eq!(stdout(&repo.git(&["config", "core.bare"])).trim(), "false");
+    let list = stdout(&repo.git(&["worktree", "list"]));
+    assert_eq!(list.lines().count(), 2, "No stray worktree: {}", list);
+}
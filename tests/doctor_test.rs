@@ -0,0 +1,109 @@
+mod common;
+
+use common::{gwik_command, stderr, stdout, TestRepo};
+
+/// gwik doctor: Healthy repository passes with only the wrapper warning
+#[test]
+fn test_doctor_healthy_repo() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature"]);
+
+    let output = repo.gwik(&["doctor"]);
+    let out = stdout(&output);
+
+    assert!(output.status.success(), "{}\n{}", out, stderr(&output));
+    assert!(out.contains("[ok] git version"), "{}", out);
+    assert!(out.contains("git worktree list works"), "{}", out);
+    assert!(out.contains(".worktrees has a .gitignore"), "{}", out);
+    assert!(
+        out.contains("[warn] Shell wrapper is not active"),
+        "{}",
+        out
+    );
+}
+
+/// Detects the shell wrapper through the environment it sets
+#[test]
+fn test_doctor_detects_wrapper() {
+    let repo = TestRepo::new();
+
+    let output = gwik_command(repo.env_dir.path())
+        .arg("doctor")
+        .current_dir(&repo.root)
+        .env("GWIK_WRAPPER", "1")
+        .output()
+        .unwrap();
+
+    assert!(stdout(&output).contains("[ok] Shell wrapper is active"));
+}
+
+/// Reports config syntax errors and unknown keys
+#[test]
+fn test_doctor_config_problems() {
+    let repo = TestRepo::new();
+
+    repo.write_local_config("worktree_dirs = \".wt\"\n");
+    repo.write_global_config("roots = [\"/nonexistent/gwik-root\"]\n");
+
+    let output = repo.gwik(&["doctor"]);
+    let out = stdout(&output);
+    assert!(output.status.success(), "Warnings only: {}", out);
+    assert!(out.contains("unknown keys: worktree_dirs"), "{}", out);
+    assert!(
+        out.contains("[warn] Root /nonexistent/gwik-root does not exist"),
+        "{}",
+        out
+    );
+
+    repo.write_local_config("worktree_dir = \n");
+    let output = repo.gwik(&["doctor"]);
+    let out = stdout(&output);
+    assert!(!output.status.success());
+    assert!(out.contains("could not be parsed"), "{}", out);
+    assert!(out.contains("fix:"), "{}", out);
+}
+
+/// Reports prunable, locked and broken worktrees
+#[test]
+fn test_doctor_worktree_problems() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "locked"]);
+    repo.gwik(&["open", "moved"]);
+    repo.git(&[
+        "worktree",
+        "lock",
+        "--reason",
+        "on usb",
+        repo.worktree_path("locked").to_str().unwrap(),
+    ]);
+    std::fs::rename(repo.worktree_path("moved"), repo.worktree_path("moved2")).unwrap();
+
+    let output = repo.gwik(&["doctor"]);
+    let out = stdout(&output);
+
+    assert!(!output.status.success());
+    assert!(out.contains("is locked (on usb)"), "{}", out);
+    assert!(out.contains("is prunable"), "{}", out);
+    assert!(out.contains("[error] Broken gitdir link"), "{}", out);
+    assert!(out.contains("gwik repair"), "{}", out);
+}
+
+/// Validates source_worktree for bare repositories
+#[test]
+fn test_doctor_bare_source_worktree() {
+    let repo = TestRepo::new_bare();
+
+    let output = repo.gwik(&["doctor"]);
+    assert!(
+        stdout(&output).contains("source_worktree main is a worktree"),
+        "{}",
+        stdout(&output)
+    );
+
+    repo.write_local_config("source_worktree = \"missing\"\n");
+    let output = repo.gwik(&["doctor"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("source_worktree missing is not a worktree"));
+}
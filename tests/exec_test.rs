@@ -0,0 +1,134 @@
+mod common;
+
+use common::{stderr, stdout, TestRepo, TestWorkspace};
+
+/// gwik exec -- <cmd>: Runs the command in each worktree with prefixed output
+#[test]
+fn test_exec_runs_in_each_worktree() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+    repo.gwik(&["open", "feature-b"]);
+
+    let output = repo.gwik(&["exec", "--", "pwd"]);
+
+    assert!(
+        output.status.success(),
+        "gwik exec failed: {}",
+        stderr(&output)
+    );
+    let out = stdout(&output);
+    assert!(
+        out.lines()
+            .any(|l| l.starts_with("[feature-a]") && l.ends_with(".worktrees/feature-a")),
+        "Output should be prefixed per worktree: {}",
+        out
+    );
+    assert!(out
+        .lines()
+        .any(|l| l.starts_with("[feature-b]") && l.ends_with(".worktrees/feature-b")));
+}
+
+/// Output after a non-UTF-8 line is still forwarded
+#[test]
+fn test_exec_forwards_non_utf8_output() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+
+    let output = repo.gwik(&[
+        "exec",
+        "--",
+        "sh",
+        "-c",
+        "printf 'before\\n\\377\\376\\nafter\\n'",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(out.contains("[feature-a] before"), "{}", out);
+    assert!(
+        out.contains("[feature-a] after"),
+        "Lines after invalid UTF-8 should not be dropped: {}",
+        out
+    );
+}
+
+/// Non-zero exit and a summary table when any worktree fails
+#[test]
+fn test_exec_reports_failures() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "good"]);
+    repo.gwik(&["open", "bad"]);
+    repo.create_file_in_worktree("bad", "fail", "");
+
+    let output = repo.gwik(&[
+        "exec",
+        "--parallel",
+        "2",
+        "--",
+        "sh",
+        "-c",
+        "test ! -e fail",
+    ]);
+
+    assert!(!output.status.success(), "Should fail when a command fails");
+    let err = stderr(&output);
+    assert!(err.contains("WORKTREE"), "Should print a summary: {}", err);
+    assert!(
+        err.lines()
+            .any(|l| l.starts_with("bad") && l.trim_end().ends_with('1')),
+        "{}",
+        err
+    );
+    assert!(
+        err.lines()
+            .any(|l| l.starts_with("good") && l.trim_end().ends_with('0')),
+        "{}",
+        err
+    );
+    assert!(err.contains("1 of 2"), "{}", err);
+}
+
+/// --filter limits the worktrees, --include-main adds the main checkout
+#[test]
+fn test_exec_filter_and_include_main() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "fix-1"]);
+    repo.gwik(&["open", "feature-x"]);
+
+    let output = repo.gwik(&["exec", "--filter", "fix-*", "--include-main", "--", "true"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let err = stderr(&output);
+    assert!(err.lines().any(|l| l.starts_with("fix-1")), "{}", err);
+    assert!(!err.contains("feature-x"), "{}", err);
+    assert!(
+        !err.lines().any(|l| l.starts_with('@')),
+        "Main is filtered out by name: {}",
+        err
+    );
+
+    let output = repo.gwik(&["exec", "--include-main", "--", "true"]);
+    assert!(stderr(&output).lines().any(|l| l.starts_with('@')));
+}
+
+/// gwik exec --all: Runs across all repositories
+#[test]
+fn test_exec_all_repositories() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    let repo_b = ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "one"]);
+    ws.gwik_in(&repo_b, &["open", "two"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["exec", "--all", "--", "true"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let err = stderr(&output);
+    assert!(err.contains("alice/repo-a/one"), "{}", err);
+    assert!(err.contains("bob/repo-b/two"), "{}", err);
+}
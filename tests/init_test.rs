@@ -121,6 +121,18 @@ fn test_init_zsh_includes_completions() {
     );
 }
 
+/// Dynamic completion keeps the frecency order of the candidates
+#[test]
+fn test_init_completion_keeps_candidate_order() {
+    let repo = TestRepo::new();
+
+    let bash = stdout(&repo.gwik(&["init", "bash"]));
+    assert!(bash.contains("compopt -o nosort"), "{}", bash);
+
+    let zsh = stdout(&repo.gwik(&["init", "zsh"]));
+    assert!(zsh.contains("compadd -V"), "{}", zsh);
+}
+
 /// Test that output can be used with eval
 #[test]
 fn test_init_output_is_valid_shell_code() {
@@ -1,6 +1,6 @@
 mod common;
 
-use common::{stdout, TestRepo};
+use common::{stderr, stdout, TestRepo, TestWorkspace};
 
 /// gwik list: Lists all worktrees in the current repository
 /// Spec: Outputs only paths, one per line
@@ -140,3 +140,151 @@ fn test_list_shows_converted_names() {
     );
     assert!(out.contains("bugfix-simple"), "Should show simple name");
 }
+
+/// gwik list --all: Works outside of any git repository
+#[test]
+fn test_list_all_outside_repository() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    let repo_b = ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "feature-a"]);
+    ws.gwik_in(&repo_b, &["open", "feature-b"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["list", "--all"]);
+
+    assert!(
+        output.status.success(),
+        "gwik list --all failed: {}",
+        stderr(&output)
+    );
+    let out = stdout(&output);
+    assert_eq!(
+        out.lines().count(),
+        2,
+        "Should list both worktrees: {}",
+        out
+    );
+    assert!(out.contains("repo-a/.worktrees/feature-a"));
+    assert!(out.contains("repo-b/.worktrees/feature-b"));
+}
+
+/// gwik list --all --group: Tree by repository with the main checkout shown
+#[test]
+fn test_list_all_grouped() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "feature/a"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["list", "--all", "--group"]);
+
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines.contains(&"github.com/alice/repo-a"), "{}", out);
+    assert!(lines.contains(&"github.com/bob/repo-b"), "{}", out);
+    assert!(
+        out.contains("(main)"),
+        "Main checkout should be shown: {}",
+        out
+    );
+    assert!(
+        out.contains("feature-a [feature/a]"),
+        "Worktree should show its branch: {}",
+        out
+    );
+}
+
+/// gwik list --all --repo/--branch: Filters by repository and branch patterns
+#[test]
+fn test_list_all_repo_and_branch_filters() {
+    let ws = TestWorkspace::new();
+    let repo_a = ws.add_repo("github.com/alice/repo-a");
+    let repo_b = ws.add_repo("github.com/bob/repo-b");
+
+    ws.gwik_in(&repo_a, &["open", "feature/one"]);
+    ws.gwik_in(&repo_a, &["open", "fix/two"]);
+    ws.gwik_in(&repo_b, &["open", "feature/three"]);
+
+    let output = ws.gwik_in(&ws.outside_dir(), &["list", "--all", "--repo", "alice/*"]);
+    let out = stdout(&output);
+    assert_eq!(out.lines().count(), 2, "{}", out);
+    assert!(!out.contains("repo-b"));
+
+    let output = ws.gwik_in(
+        &ws.outside_dir(),
+        &["list", "--all", "--branch", "feature/*"],
+    );
+    let out = stdout(&output);
+    assert_eq!(out.lines().count(), 2, "{}", out);
+    assert!(out.contains("feature-one"));
+    assert!(out.contains("feature-three"));
+
+    // Filters apply to the main checkout in the tree too
+    let output = ws.gwik_in(
+        &ws.outside_dir(),
+        &["list", "--all", "--group", "--branch", "feature/*"],
+    );
+    let out = stdout(&output);
+    assert!(!out.contains("(main)"), "{}", out);
+    assert!(out.contains("feature-one"), "{}", out);
+}
+
+/// gwik list --dirty / --stale: Filters by worktree state
+#[test]
+fn test_list_dirty_and_stale_filters() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "clean"]);
+    repo.gwik(&["open", "dirty"]);
+    repo.create_file_in_worktree("dirty", "scratch.txt", "wip");
+
+    let output = repo.gwik(&["list", "--dirty"]);
+    let out = stdout(&output);
+    assert_eq!(out.lines().count(), 1, "{}", out);
+    assert!(out.contains("dirty"));
+
+    // Fresh commits are not stale
+    let output = repo.gwik(&["list", "--stale", "30"]);
+    assert!(stdout(&output).trim().is_empty());
+
+    let output = repo.gwik(&["list", "--stale", "0"]);
+    assert_eq!(stdout(&output).lines().count(), 2);
+
+    let output = repo.gwik(&["list", "--stale", "999999999999999"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).trim().is_empty());
+
+    // Worktrees git cannot inspect are reported, not silently treated as clean
+    std::fs::remove_dir_all(repo.worktree_path("dirty")).unwrap();
+    let output = repo.gwik(&["list", "--dirty"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).trim().is_empty());
+    assert!(
+        stderr(&output).contains("Warning: Skipping"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+/// gwik list --include-main: The main checkout is listed first
+#[test]
+fn test_list_include_main() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+
+    let output = repo.gwik(&["list", "--include-main"]);
+
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", out);
+    assert_eq!(
+        lines[0],
+        repo.root.canonicalize().unwrap().display().to_string()
+    );
+    assert!(lines[1].contains("feature-a"));
+}
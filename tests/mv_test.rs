@@ -0,0 +1,155 @@
+mod common;
+
+use common::{stderr, stdout, TestRepo};
+
+/// gwik mv <old> <new>: Renames the branch and moves the worktree directory
+#[test]
+fn test_mv_renames_branch_and_directory() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature/old"]);
+
+    let output = repo.gwik(&["mv", "feature-old", "feature/new"]);
+
+    assert!(
+        output.status.success(),
+        "gwik mv failed: {}",
+        stderr(&output)
+    );
+    assert!(!repo.worktree_exists("feature-old"));
+    assert!(repo.worktree_exists("feature-new"));
+
+    let branches = stdout(&repo.git(&["branch", "--list"]));
+    assert!(branches.contains("feature/new"), "{}", branches);
+    assert!(!branches.contains("feature/old"), "{}", branches);
+
+    // Not inside the worktree, so no cd directive
+    assert!(stdout(&output).trim().is_empty());
+
+    // cd finds it by the new name
+    let output = repo.gwik(&["cd", "feature-new"]);
+    assert!(output.status.success());
+}
+
+/// Prints a cd directive when run from inside the moved worktree
+#[test]
+fn test_mv_from_inside_outputs_cd() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "inside"]);
+    let subdir = repo.worktree_path("inside").join("sub");
+    std::fs::create_dir_all(&subdir).unwrap();
+
+    let output = repo.gwik_in(&subdir, &["mv", "inside", "renamed"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(
+        out.trim().ends_with(".worktrees/renamed/sub"),
+        "Should cd into the moved worktree: {}",
+        out
+    );
+}
+
+/// Follows the worktree when the worktree directory is reached through a symlink
+#[test]
+fn test_mv_from_inside_through_symlink() {
+    let repo = TestRepo::new();
+
+    let real = repo.root.join("real-worktrees");
+    std::fs::create_dir_all(&real).unwrap();
+    std::os::unix::fs::symlink(&real, repo.root.join("wt")).unwrap();
+    repo.write_local_config("worktree_dir = \"wt\"\n");
+    repo.gwik(&["open", "inside"]);
+
+    let output = repo.gwik_in(&real.join("inside"), &["mv", "inside", "renamed"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(
+        out.trim().trim_end_matches('/').ends_with("/renamed"),
+        "Should cd into the moved worktree: {}",
+        out
+    );
+}
+
+/// Visit history follows the moved worktree
+#[test]
+fn test_mv_updates_history() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "visited"]);
+    repo.gwik(&["open", "other"]);
+    repo.gwik(&["mv", "visited", "renamed"]);
+
+    let output = repo.gwik_in(&repo.worktree_path("other"), &["cd", "-"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).trim().ends_with(".worktrees/renamed"),
+        "cd - should go to the new path: {}",
+        stdout(&output)
+    );
+}
+
+/// --update-upstream points the upstream at the new branch name
+#[test]
+fn test_mv_update_upstream() {
+    let repo = TestRepo::new();
+
+    repo.create_remote_branch("origin", "tracked");
+    repo.gwik(&["open", "origin/tracked"]);
+    let name = if repo.worktree_exists("origin-tracked") {
+        "origin-tracked"
+    } else {
+        "tracked"
+    };
+
+    let output = repo.gwik(&["mv", "--update-upstream", name, "tracked-v2"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let merge = stdout(&repo.git(&["config", "branch.tracked-v2.merge"]));
+    assert_eq!(merge.trim(), "refs/heads/tracked-v2");
+    let remote = stdout(&repo.git(&["config", "branch.tracked-v2.remote"]));
+    assert_eq!(remote.trim(), "origin");
+}
+
+/// The branch rename is undone when the directory cannot be moved
+#[test]
+fn test_mv_rolls_back_on_failure() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "locked"]);
+    repo.git(&[
+        "worktree",
+        "lock",
+        repo.worktree_path("locked").to_str().unwrap(),
+    ]);
+
+    let output = repo.gwik(&["mv", "locked", "unlocked"]);
+
+    assert!(!output.status.success(), "Moving a locked worktree fails");
+    assert!(repo.worktree_exists("locked"));
+    let branches = stdout(&repo.git(&["branch", "--list"]));
+    assert!(branches.contains("locked"), "{}", branches);
+    assert!(
+        !branches.contains("unlocked"),
+        "Rename rolled back: {}",
+        branches
+    );
+}
+
+/// Refuses to overwrite an existing branch
+#[test]
+fn test_mv_refuses_existing_branch() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "source"]);
+    repo.create_branch("taken");
+
+    let output = repo.gwik(&["mv", "source", "taken"]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already exists"));
+    assert!(repo.worktree_exists("source"));
+}
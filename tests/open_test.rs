@@ -283,3 +283,576 @@ fn test_open_nested_branch_name() {
         "Nested slashes should all be converted"
     );
 }
+
+/// --move-changes carries staged, unstaged and untracked changes into the new worktree
+#[test]
+fn test_open_move_changes() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.root.join("README.md"), "modified\n").unwrap();
+    std::fs::write(repo.root.join("staged.txt"), "staged\n").unwrap();
+    repo.git(&["add", "staged.txt"]);
+    std::fs::write(repo.root.join("untracked.txt"), "untracked\n").unwrap();
+    let status_before = stdout(&repo.git(&["status", "--porcelain"]));
+
+    let output = repo.gwik(&["open", "carry", "--move-changes", "-u"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let worktree = repo.worktree_path("carry");
+    let status_after = stdout(&repo.git_in(&worktree, &["status", "--porcelain"]));
+    assert_eq!(status_after, status_before);
+
+    // The source is clean and no stash is left behind
+    assert_eq!(stdout(&repo.git(&["status", "--porcelain"])), "");
+    assert_eq!(stdout(&repo.git(&["stash", "list"])), "");
+}
+
+/// --copy-changes keeps the changes in the source; untracked files need -u
+#[test]
+fn test_open_copy_changes() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.root.join("README.md"), "modified\n").unwrap();
+    std::fs::write(repo.root.join("untracked.txt"), "untracked\n").unwrap();
+
+    let output = repo.gwik(&["open", "copy", "--copy-changes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let worktree = repo.worktree_path("copy");
+    assert_eq!(
+        std::fs::read_to_string(worktree.join("README.md")).unwrap(),
+        "modified\n"
+    );
+    assert!(!worktree.join("untracked.txt").exists());
+    assert_eq!(
+        stdout(&repo.git(&["status", "--porcelain"])),
+        " M README.md\n?? untracked.txt\n"
+    );
+    assert_eq!(stdout(&repo.git(&["stash", "list"])), "");
+}
+
+/// Changes are restored to the source when they cannot be applied
+#[test]
+fn test_open_move_changes_restores_on_conflict() {
+    let repo = TestRepo::new();
+    repo.git(&["checkout", "-q", "-b", "other"]);
+    repo.commit_file_in(&repo.root, "README.md", "other\n");
+    repo.git(&["checkout", "-q", "-"]);
+    std::fs::write(repo.root.join("README.md"), "modified\n").unwrap();
+
+    let output = repo.gwik(&["open", "other", "--move-changes", "--yes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("restored"), "{}", stderr(&output));
+
+    assert_eq!(
+        std::fs::read_to_string(repo.root.join("README.md")).unwrap(),
+        "modified\n"
+    );
+    assert_eq!(stdout(&repo.git(&["stash", "list"])), "");
+    let target = stdout(&repo.git_in(&repo.worktree_path("other"), &["status", "--porcelain"]));
+    assert_eq!(target, "", "New worktree should be left clean");
+}
+
+/// Fails without touching anything when there is nothing to carry
+#[test]
+fn test_open_move_changes_requires_changes() {
+    let repo = TestRepo::new();
+
+    let output = repo.gwik(&["open", "nothing", "--move-changes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No local changes"));
+    assert!(!repo.worktree_exists("nothing"));
+}
+
+/// --pr <n> fetches the pull request head into a pr-<n> branch
+#[test]
+fn test_open_pull_request() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    let sha = repo.push_fake_ref("origin", "HEAD", "refs/pull/5/head", "pr.txt");
+
+    let output = repo.gwik(&["open", "--pr", "5"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    assert!(repo.worktree_exists("pr-5"));
+    assert_eq!(repo.rev_parse("pr-5"), sha);
+    let recorded = stdout(&repo.git(&["config", "branch.pr-5.gwik-pr"]));
+    assert_eq!(recorded.trim(), "5");
+}
+
+/// The fetched pull request head wins over a stale local pr-<n> or a remote branch of that name
+#[test]
+fn test_open_pull_request_uses_fetched_head() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "pr-5");
+    let first = repo.push_fake_ref("origin", "HEAD", "refs/pull/5/head", "pr.txt");
+
+    let output = repo.gwik(&["open", "--pr", "5"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        repo.rev_parse("pr-5"),
+        first,
+        "Remote pr-5 branch is not the PR"
+    );
+    repo.git(&[
+        "worktree",
+        "remove",
+        repo.worktree_path("pr-5").to_str().unwrap(),
+    ]);
+
+    // Reopening fast-forwards the existing branch without asking
+    let second = repo.push_fake_ref("origin", &first, "refs/pull/5/head", "more.txt");
+    let output = repo.gwik(&["open", "--pr", "5"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.rev_parse("pr-5"), second);
+    repo.git(&[
+        "worktree",
+        "remove",
+        repo.worktree_path("pr-5").to_str().unwrap(),
+    ]);
+
+    // Local commits on the branch are never thrown away
+    repo.git(&["checkout", "-q", "pr-5"]);
+    repo.commit_file_in(&repo.root, "local.txt", "local\n");
+    repo.git(&["checkout", "-q", "-"]);
+    let output = repo.gwik(&["open", "--pr", "5"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("not in the pull request"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!repo.worktree_exists("pr-5"));
+}
+
+/// pr/<n> shorthand, with merge request refs for GitLab
+#[test]
+fn test_open_pull_request_shorthand_gitlab() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.write_local_config("forge = \"gitlab\"\n");
+    let sha = repo.push_fake_ref("origin", "HEAD", "refs/merge-requests/7/head", "mr.txt");
+
+    let output = repo.gwik(&["open", "pr/7"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.rev_parse("pr-7"), sha);
+}
+
+/// A branch name given with --pr is used instead of pr-<n>; unknown PRs fail
+#[test]
+fn test_open_pull_request_named_and_missing() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.push_fake_ref("origin", "HEAD", "refs/pull/5/head", "pr.txt");
+
+    let output = repo.gwik(&["open", "review", "--pr", "5"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(repo.worktree_exists("review"));
+
+    let output = repo.gwik(&["open", "--pr", "99"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Failed to fetch PR #99"));
+    assert!(!repo.worktree_exists("pr-99"));
+}
+
+/// A plain branch name that only exists on a remote is checked out tracking it
+#[test]
+fn test_open_guesses_remote_branch() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "feature-x");
+
+    let output = repo.gwik(&["open", "feature-x"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("tracking origin/feature-x"));
+
+    let upstream = stdout(&repo.git_in(
+        &repo.worktree_path("feature-x"),
+        &["rev-parse", "--abbrev-ref", "@{u}"],
+    ));
+    assert_eq!(upstream.trim(), "origin/feature-x");
+}
+
+/// Several matching remotes are an error unless checkout.defaultRemote picks one
+#[test]
+fn test_open_ambiguous_remote_branch() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "dup");
+    repo.create_remote_branch("upstream", "dup");
+
+    let output = repo.gwik(&["open", "dup"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("origin/dup, upstream/dup"),
+        "{}",
+        stderr(&output)
+    );
+
+    repo.git(&["config", "checkout.defaultRemote", "upstream"]);
+    let output = repo.gwik(&["open", "dup"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("tracking upstream/dup"));
+}
+
+/// Remote names containing slashes are split correctly
+#[test]
+fn test_open_remote_with_slash() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("upstream/team", "fix");
+
+    let output = repo.gwik(&["open", "upstream/team/fix"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let branches = stdout(&repo.git(&["branch", "--list", "fix"]));
+    assert!(branches.contains("fix"), "Local branch should be 'fix'");
+    let remote = stdout(&repo.git(&["config", "branch.fix.remote"]));
+    assert_eq!(remote.trim(), "upstream/team");
+}
+
+/// Push a branch to origin and forget the remote-tracking ref, as if pushed by someone else
+fn push_unfetched_branch(repo: &TestRepo, branch: &str) {
+    repo.create_remote_branch("origin", "base");
+    repo.git(&[
+        "push",
+        "-q",
+        "origin",
+        &format!("HEAD:refs/heads/{}", branch),
+    ]);
+    repo.git(&[
+        "update-ref",
+        "-d",
+        &format!("refs/remotes/origin/{}", branch),
+    ]);
+}
+
+/// fetch = "missing" fetches when the branch is not known yet
+#[test]
+fn test_open_fetches_missing_branch() {
+    let repo = TestRepo::new();
+    push_unfetched_branch(&repo, "late");
+    repo.write_local_config("fetch = \"missing\"\n");
+
+    let output = repo.gwik(&["open", "origin/late"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Fetched origin/late"));
+    assert!(stderr(&output).contains("tracking origin/late"));
+}
+
+/// After fetching, a new branch forks from the fresh remote default branch
+#[test]
+fn test_open_new_branch_forks_from_fetched_base() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    // Someone else moved the default branch since the last fetch
+    let sha = repo.push_fake_ref("origin", "HEAD", "refs/heads/trunk", "new.txt");
+    repo.git(&[
+        "symbolic-ref",
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/trunk",
+    ]);
+    repo.write_local_config("fetch = \"missing\"\n");
+
+    let output = repo.gwik(&["open", "fresh"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Fetched all remotes"));
+    assert_eq!(repo.rev_parse("fresh"), sha);
+    assert!(
+        !repo.git(&["config", "branch.fresh.merge"]).status.success(),
+        "New branch should not track the base"
+    );
+
+    // Without a fetch, new branches still start at the local HEAD
+    let output = repo.gwik(&["open", "local", "--no-fetch"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.rev_parse("local"), repo.rev_parse("HEAD"));
+}
+
+/// --fetch and --no-fetch override the configured policy
+#[test]
+fn test_open_fetch_flags() {
+    let repo = TestRepo::new();
+    push_unfetched_branch(&repo, "late");
+
+    let output = repo.gwik(&["open", "late", "--fetch"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("tracking origin/late"));
+
+    push_unfetched_branch(&repo, "later");
+    repo.write_local_config("fetch = \"always\"\n");
+    let output = repo.gwik(&["open", "later", "--no-fetch"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("Fetched"));
+    assert!(!stderr(&output).contains("tracking"));
+}
+
+/// A hanging or unreachable remote only produces a warning
+#[test]
+fn test_open_fetch_timeout_falls_back() {
+    let repo = TestRepo::new();
+    repo.git(&["config", "protocol.ext.allow", "always"]);
+    repo.git(&["remote", "add", "origin", "ext::sleep 10"]);
+    repo.write_local_config("fetch = \"always\"\nfetch_timeout = \"1s\"\n");
+
+    let output = repo.gwik(&["open", "offline"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("timed out after 1s"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo.worktree_exists("offline"));
+}
+
+/// --push pushes a new branch and sets its upstream
+#[test]
+fn test_open_push_new_branch() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+
+    let output = repo.gwik(&["open", "pushed", "--push"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Pushed pushed to origin"));
+
+    let remote = stdout(&repo.git(&["ls-remote", "origin", "refs/heads/pushed"]));
+    assert!(
+        !remote.trim().is_empty(),
+        "Branch should exist on the remote"
+    );
+    let upstream = stdout(&repo.git_in(
+        &repo.worktree_path("pushed"),
+        &["rev-parse", "--abbrev-ref", "@{u}"],
+    ));
+    assert_eq!(upstream.trim(), "origin/pushed");
+}
+
+/// upstream_remote configures tracking up front; status shows it as not pushed yet
+#[test]
+fn test_open_sets_upstream_remote() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    repo.write_local_config("upstream_remote = \"origin\"\n");
+
+    let output = repo.gwik(&["open", "tracked"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Set upstream to origin/tracked"));
+
+    let merge = stdout(&repo.git(&["config", "branch.tracked.merge"]));
+    assert_eq!(merge.trim(), "refs/heads/tracked");
+
+    let status = stdout(&repo.gwik(&["status"]));
+    assert!(status.contains("origin/tracked (gone)"), "{}", status);
+}
+
+/// A failed push only warns
+#[test]
+fn test_open_push_failure_warns() {
+    let repo = TestRepo::new();
+    repo.git(&["remote", "add", "origin", "/nonexistent/gwik-remote"]);
+    repo.write_local_config("push_new_branches = true\n");
+
+    let output = repo.gwik(&["open", "unpushed"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Warning: git push failed"));
+    assert!(repo.worktree_exists("unpushed"));
+}
+
+/// Hooks get the documented environment; a failing pre-open hook aborts
+#[test]
+fn test_open_hooks() {
+    let repo = TestRepo::new();
+    repo.write_local_config(
+        r#"[hooks]
+pre-open = ["test \"$GWIK_BRANCH\" != blocked"]
+post-open = ["echo \"$GWIK_EVENT $GWIK_BRANCH $GWIK_DST $GWIK_REPO\" > \"$GWIK_SRC/hook.log\""]
+"#,
+    );
+
+    let output = repo.gwik(&["open", "blocked"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("pre-open hook failed"));
+    assert!(!repo.worktree_exists("blocked"));
+    let branches = stdout(&repo.git(&["branch", "--list", "blocked"]));
+    assert!(branches.trim().is_empty(), "Nothing should be created");
+
+    let output = repo.gwik(&["open", "feature/hooked"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let log = std::fs::read_to_string(repo.root.join("hook.log")).unwrap();
+    assert_eq!(
+        log.trim(),
+        format!(
+            "post-open feature/hooked {} {}",
+            repo.worktree_path("feature-hooked").display(),
+            repo.root.display()
+        )
+    );
+}
+
+/// Structured cmds get env vars and a working directory; their output stays off stdout
+#[test]
+fn test_open_structured_cmds() {
+    let repo = TestRepo::new();
+    repo.write_local_config(
+        r#"cmds = [
+    { run = "echo \"$GREETING $GWIK_BRANCH\" > greeting.txt", cwd = "dst", env = { GREETING = "hello" } },
+    "echo noise",
+]
+"#,
+    );
+
+    let output = repo.gwik(&["open", "feature/env"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("cd "), "{}", stdout(&output));
+
+    let greeting =
+        std::fs::read_to_string(repo.worktree_path("feature-env").join("greeting.txt")).unwrap();
+    assert_eq!(greeting.trim(), "hello feature/env");
+}
+
+/// A timed-out command fails open unless it has continue_on_error
+#[test]
+fn test_open_cmd_timeout() {
+    let repo = TestRepo::new();
+    repo.write_local_config(
+        r#"cmds = [{ run = "sleep 10", timeout = "1s", continue_on_error = true }, "touch after"]"#,
+    );
+    let output = repo.gwik(&["open", "lenient"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Warning: Post-creation command timed out after 1s"));
+
+    repo.write_local_config(r#"cmds = [{ run = "sleep 10", timeout = "1s" }]"#);
+    let output = repo.gwik(&["open", "strict"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("timed out after 1s: sleep 10"));
+}
+
+/// A timeout also kills the processes the command started
+#[test]
+fn test_open_cmd_timeout_kills_children() {
+    let repo = TestRepo::new();
+    repo.write_local_config(
+        r#"cmds = [{ run = "(sleep 2; touch late) & wait", cwd = "dst", timeout = "1s" }]"#,
+    );
+
+    let start = std::time::Instant::now();
+    let output = repo.gwik(&["open", "killed"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("timed out after 1s"));
+
+    std::thread::sleep(std::time::Duration::from_secs(3).saturating_sub(start.elapsed()));
+    assert!(
+        !repo.worktree_path("killed").join("late").exists(),
+        "The background command should have been killed"
+    );
+}
+
+/// Ctrl-C reaches commands that run in their own process group for their timeout
+#[cfg(unix)]
+#[test]
+fn test_open_cmd_timeout_forwards_interrupt() {
+    let repo = TestRepo::new();
+    repo.write_local_config(
+        r#"cmds = [{ run = "trap 'touch interrupted; exit 1' INT; touch started; while true; do sleep 0.1; done", cwd = "dst", timeout = "30s" }]"#,
+    );
+
+    let mut child = common::gwik_command(repo.env_dir.path())
+        .args(["open", "interrupted"])
+        .current_dir(&repo.root)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let worktree = repo.worktree_path("interrupted");
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !worktree.join("started").exists() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(worktree.join("started").exists(), "Command should start");
+
+    // The terminal only signals gwik's own process group
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    let status = child.wait().unwrap();
+    assert!(!status.success());
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !worktree.join("interrupted").exists() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(
+        worktree.join("interrupted").exists(),
+        "The command should have received the interrupt"
+    );
+}
+
+/// An invalid timeout fails only the command, not gwik as a whole
+#[test]
+fn test_open_cmd_invalid_timeout() {
+    let repo = TestRepo::new();
+    repo.write_local_config(r#"cmds = [{ run = "true", timeout = "soon" }]"#);
+
+    let output = repo.gwik(&["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = repo.gwik(&["open", "invalid"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Invalid timeout for `true`"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+/// Consecutive commands in one parallel_group run concurrently
+#[test]
+fn test_open_parallel_cmds() {
+    let repo = TestRepo::new();
+    // Each command waits for the other's marker, so this only finishes when run in parallel
+    repo.write_local_config(
+        r#"cmds = [
+    { run = "touch a; while [ ! -e b ]; do sleep 0.1; done", cwd = "dst", timeout = "10s", parallel_group = "deps" },
+    { run = "touch b; while [ ! -e a ]; do sleep 0.1; done", cwd = "dst", timeout = "10s", parallel_group = "deps" },
+]
+"#,
+    );
+
+    let output = repo.gwik(&["open", "parallel"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+/// [[cmds]] entries only run when their branch glob and path conditions hold
+#[test]
+fn test_open_conditional_cmds() {
+    let repo = TestRepo::new();
+    repo.commit_file_in(&repo.root, "package.json", "{}\n");
+    repo.write_local_config(
+        r#"[[cmds]]
+run = "touch frontend.txt"
+cwd = "dst"
+when.branch = "frontend/*"
+
+[[cmds]]
+run = "touch infra.txt"
+cwd = "dst"
+when = { branch = "infra/*" }
+
+[[cmds]]
+run = "touch node.txt"
+cwd = "dst"
+when.exists = "package.json"
+
+[[cmds]]
+run = "touch python.txt"
+cwd = "dst"
+when.exists = "pyproject.toml"
+"#,
+    );
+
+    let output = repo.gwik(&["open", "frontend/login"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let worktree = repo.worktree_path("frontend-login");
+    assert!(worktree.join("frontend.txt").exists());
+    assert!(!worktree.join("infra.txt").exists());
+    assert!(worktree.join("node.txt").exists());
+    assert!(!worktree.join("python.txt").exists());
+}
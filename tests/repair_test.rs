@@ -0,0 +1,87 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, TestRepo};
+
+/// gwik repair: Fixes worktrees after worktree_dir was moved by hand
+#[test]
+fn test_repair_moved_worktree_dir() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature"]);
+    fs::rename(repo.root.join(".worktrees"), repo.root.join(".wt")).unwrap();
+    repo.write_local_config("worktree_dir = \".wt\"\n");
+
+    let output = repo.gwik(&["repair", "--check"]);
+    assert!(!output.status.success(), "Check should fail");
+    assert!(stderr(&output).contains("Broken gitdir link"));
+
+    let output = repo.gwik(&["repair"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // git sees the worktree again at its new location
+    let worktree = repo.root.join(".wt").join("feature");
+    repo.git_in(&worktree, &["status"]);
+    let output = repo.gwik(&["cd", "feature"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = repo.gwik(&["repair", "--check"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+/// Fixes links in both directions after the repository itself was moved
+#[test]
+fn test_repair_moved_repository() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature"]);
+
+    let moved = tempfile::TempDir::new().unwrap();
+    let new_root = moved.path().join("moved");
+    fs::rename(&repo.root, &new_root).unwrap();
+
+    let output = repo.gwik_in(&new_root, &["repair"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let worktree = new_root.join(".worktrees").join("feature");
+    repo.git_in(&worktree, &["status"]);
+
+    let output = repo.gwik_in(&new_root, &["repair", "--check"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+/// Migrates worktrees into a newly configured worktree_dir
+#[test]
+fn test_repair_migrates_into_worktree_dir() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature"]);
+    repo.write_local_config("worktree_dir = \".wt\"\n");
+
+    let output = repo.gwik(&["repair", "--check"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Outside .wt"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = repo.gwik(&["repair", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(repo.root.join(".wt").join("feature").exists());
+    assert!(!repo.worktree_path("feature").exists());
+    assert!(repo.root.join(".wt").join(".gitignore").exists());
+}
+
+/// Reports nothing to do on a healthy repository
+#[test]
+fn test_repair_healthy() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature"]);
+
+    let output = repo.gwik(&["repair", "--check"]);
+    assert!(output.status.success());
+    assert!(stderr(&output).contains("healthy"));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::{stderr, stdout, TestRepo};
+
+/// gwik status: Shows every worktree and the main checkout
+#[test]
+fn test_status_lists_main_and_worktrees() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+    repo.gwik(&["open", "feature/b"]);
+
+    let output = repo.gwik(&["status"]);
+
+    assert!(
+        output.status.success(),
+        "gwik status failed: {}",
+        stderr(&output)
+    );
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 4, "Header, main and two worktrees: {}", out);
+    assert!(lines[0].starts_with("WORKTREE"));
+    assert!(lines[1].starts_with('@'), "Main checkout first: {}", out);
+    assert!(out.contains("feature-b"));
+    assert!(
+        !out.contains("\x1b["),
+        "No colors when stdout is not a terminal"
+    );
+}
+
+/// gwik status --json: Reports change counts, stashes and merge state
+#[test]
+fn test_status_json_counts() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+    repo.gwik(&["open", "feature-b"]);
+    let wt_a = repo.worktree_path("feature-a");
+    let wt_b = repo.worktree_path("feature-b");
+
+    // feature-a: one staged, one unstaged, one untracked file and a stash
+    std::fs::write(wt_a.join("README.md"), "stashed\n").unwrap();
+    repo.git_in(&wt_a, &["stash"]);
+    repo.create_file_in_worktree("feature-a", "staged.txt", "s");
+    repo.stage_file_in_worktree("feature-a", "staged.txt");
+    std::fs::write(wt_a.join("README.md"), "changed\n").unwrap();
+    repo.create_file_in_worktree("feature-a", "untracked.txt", "u");
+
+    // feature-b: a commit not merged into the main branch
+    std::fs::write(wt_b.join("b.txt"), "b\n").unwrap();
+    repo.git_in(&wt_b, &["add", "."]);
+    repo.git_in(&wt_b, &["commit", "-m", "b"]);
+
+    let output = repo.gwik(&["status", "--json"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let entries: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let main = &entries[0];
+    assert_eq!(main["name"], "@");
+    assert_eq!(main["is_main"], true);
+
+    let a = entries.iter().find(|e| e["name"] == "feature-a").unwrap();
+    assert_eq!(a["staged"], 1);
+    assert_eq!(a["unstaged"], 1);
+    assert_eq!(a["untracked"], 1);
+    assert_eq!(a["stashes"], 1);
+    assert_eq!(a["merged"], true);
+    assert!(a["last_commit_time"].is_u64());
+
+    let b = entries.iter().find(|e| e["name"] == "feature-b").unwrap();
+    assert_eq!(b["merged"], false);
+    assert_eq!(b["untracked"], 0);
+}
+
+/// A deleted worktree directory is shown as missing instead of failing the dashboard
+#[test]
+fn test_status_missing_worktree() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+    repo.gwik(&["open", "feature-gone"]);
+    std::fs::remove_dir_all(repo.worktree_path("feature-gone")).unwrap();
+
+    let output = repo.gwik(&["status"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    let gone = out
+        .lines()
+        .find(|l| l.starts_with("feature-gone"))
+        .unwrap_or_else(|| panic!("Missing worktree should have a row: {}", out));
+    assert!(gone.ends_with("missing"), "{}", out);
+    assert!(out.lines().any(|l| l.starts_with("feature-a")), "{}", out);
+
+    let output = repo.gwik(&["status", "--json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let entries: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let gone = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["name"] == "feature-gone")
+        .unwrap();
+    assert_eq!(gone["error"], "missing");
+    let a = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["name"] == "feature-a")
+        .unwrap();
+    assert_eq!(a["staged"], 0);
+}
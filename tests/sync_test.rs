@@ -0,0 +1,187 @@
+mod common;
+
+use std::path::Path;
+
+use common::{stderr, TestRepo};
+
+fn is_ancestor(repo: &TestRepo, ancestor: &str, rev: &str) -> bool {
+    repo.git(&["merge-base", "--is-ancestor", ancestor, rev])
+        .status
+        .success()
+}
+
+/// gwik sync: Rebases clean worktree branches onto the main branch
+#[test]
+fn test_sync_rebases_worktrees() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-a"]);
+    repo.commit_file_in(&repo.worktree_path("feature-a"), "a.txt", "a\n");
+    repo.commit_file_in(&repo.root, "main.txt", "main\n");
+
+    let output = repo.gwik(&["sync"]);
+
+    assert!(
+        output.status.success(),
+        "gwik sync failed: {}",
+        stderr(&output)
+    );
+    assert!(
+        is_ancestor(&repo, "HEAD", "feature-a"),
+        "feature-a should contain the new main commit"
+    );
+    let merges = common::stdout(&repo.git(&["rev-list", "--merges", "feature-a"]));
+    assert!(merges.trim().is_empty(), "Rebase should not create merges");
+}
+
+/// gwik sync --merge: Merges the base into each branch
+#[test]
+fn test_sync_merge_mode() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "feature-m"]);
+    repo.commit_file_in(&repo.worktree_path("feature-m"), "m.txt", "m\n");
+    repo.commit_file_in(&repo.root, "main.txt", "main\n");
+
+    let output = repo.gwik(&["sync", "--merge"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(is_ancestor(&repo, "HEAD", "feature-m"));
+    let merges = common::stdout(&repo.git(&["rev-list", "--merges", "feature-m"]));
+    assert!(
+        !merges.trim().is_empty(),
+        "Merge mode should create a merge"
+    );
+}
+
+/// Dirty worktrees are skipped, and --dry-run changes nothing
+#[test]
+fn test_sync_skips_dirty_and_dry_run() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "dirty"]);
+    repo.gwik(&["open", "clean"]);
+    repo.create_file_in_worktree("dirty", "wip.txt", "wip");
+    repo.commit_file_in(&repo.root, "main.txt", "main\n");
+    let clean_before = repo.rev_parse("clean");
+
+    let output = repo.gwik(&["sync", "--dry-run"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let err = stderr(&output);
+    assert!(
+        err.contains("dirty: skipped (uncommitted changes)"),
+        "{}",
+        err
+    );
+    assert!(err.contains("clean: would rebase"), "{}", err);
+    assert_eq!(
+        repo.rev_parse("clean"),
+        clean_before,
+        "Dry run must not move"
+    );
+
+    let output = repo.gwik(&["sync"]);
+    assert!(output.status.success());
+    assert!(is_ancestor(&repo, "HEAD", "clean"));
+    assert!(
+        !is_ancestor(&repo, "HEAD", "dirty"),
+        "Dirty branch untouched"
+    );
+}
+
+/// Conflicting worktrees are left mid-rebase and reported; others still sync
+#[test]
+fn test_sync_reports_conflicts_and_continues() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "conflicting"]);
+    repo.gwik(&["open", "other"]);
+    repo.commit_file_in(&repo.worktree_path("conflicting"), "README.md", "branch\n");
+    repo.commit_file_in(&repo.root, "README.md", "main\n");
+
+    let output = repo.gwik(&["sync"]);
+
+    assert!(
+        !output.status.success(),
+        "Conflicts should fail the command"
+    );
+    let err = stderr(&output);
+    assert!(err.contains("conflicting: CONFLICT"), "{}", err);
+    assert!(err.contains("git rebase --abort"), "{}", err);
+    assert!(is_ancestor(&repo, "HEAD", "other"), "Other worktree synced");
+
+    let status = common::stdout(&repo.git_in(&repo.worktree_path("conflicting"), &["status"]));
+    assert!(
+        status.contains("rebase"),
+        "Rebase left in progress: {}",
+        status
+    );
+}
+
+/// Pull request worktrees are fast-forwarded to the latest pull request head
+#[test]
+fn test_sync_updates_pull_requests() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "base");
+    let first = repo.push_fake_ref("origin", "HEAD", "refs/pull/5/head", "pr.txt");
+    repo.gwik(&["open", "--pr", "5"]);
+
+    let second = repo.push_fake_ref("origin", &first, "refs/pull/5/head", "more.txt");
+
+    let output = repo.gwik(&["sync", "--dry-run"]);
+    assert!(
+        stderr(&output).contains("would fetch PR #5 and fast-forward pr-5"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(repo.rev_parse("pr-5"), first, "Dry run must not update");
+
+    // Even with merge.ff=false the branch is only ever fast-forwarded
+    repo.git(&["config", "merge.ff", "false"]);
+
+    let output = repo.gwik(&["sync"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.rev_parse("pr-5"), second);
+}
+
+/// An error in one worktree is reported without stopping the others
+#[test]
+fn test_sync_continues_after_errors() {
+    let repo = TestRepo::new();
+
+    repo.gwik(&["open", "broken"]);
+    repo.gwik(&["open", "other"]);
+    // A pull request on a remote that does not exist cannot be fetched
+    repo.git(&["config", "branch.broken.gwik-pr", "7"]);
+    repo.git(&["config", "branch.broken.gwik-pr-remote", "nowhere"]);
+    repo.commit_file_in(&repo.root, "main.txt", "main\n");
+
+    let output = repo.gwik(&["sync"]);
+
+    assert!(!output.status.success(), "Errors should fail the command");
+    let err = stderr(&output);
+    assert!(err.contains("broken: ERROR"), "{}", err);
+    assert!(err.contains("1 worktree(s) failed: broken"), "{}", err);
+    assert!(is_ancestor(&repo, "HEAD", "other"), "Other worktree synced");
+}
+
+/// --dry-run does not fetch, so remote-tracking refs are not pruned
+#[test]
+fn test_sync_dry_run_does_not_fetch() {
+    let repo = TestRepo::new();
+    repo.create_remote_branch("origin", "gone");
+    // Delete it on the remote only, keeping the local origin/gone
+    let url = common::stdout(&repo.git(&["remote", "get-url", "origin"]));
+    repo.git_in(Path::new(url.trim()), &["branch", "-D", "gone"]);
+
+    let output = repo.gwik(&["sync", "--dry-run"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        repo.git(&["rev-parse", "--verify", "origin/gone"])
+            .status
+            .success(),
+        "Dry run must not prune remote-tracking refs"
+    );
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestRepo};
+
+/// gwik close --trash keeps everything, and gwik restore puts it back
+#[test]
+fn test_trash_and_restore() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-trash"]);
+    let worktree = repo.worktree_path("feature-trash");
+    fs::write(worktree.join("README.md"), "edited\n").unwrap();
+    fs::write(worktree.join(".gitignore"), "build/\n").unwrap();
+    fs::create_dir_all(worktree.join("build")).unwrap();
+    fs::write(worktree.join("build/output"), "artifact").unwrap();
+
+    let output = repo.gwik(&["close", "feature-trash", "--trash"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Moved worktree to trash: feature-trash"));
+    assert!(!worktree.exists());
+    let worktrees = stdout(&repo.git(&["worktree", "list"]));
+    assert!(!worktrees.contains("feature-trash"), "{}", worktrees);
+
+    let list = stdout(&repo.gwik(&["trash", "list"]));
+    assert!(list.starts_with("NAME"), "{}", list);
+    assert!(list.contains("feature-trash"), "{}", list);
+
+    let output = repo.gwik(&["restore", "feature-trash"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), format!("cd {}", worktree.display()));
+    assert_eq!(
+        fs::read_to_string(worktree.join("README.md")).unwrap(),
+        "edited\n"
+    );
+    assert_eq!(
+        fs::read_to_string(worktree.join("build/output")).unwrap(),
+        "artifact"
+    );
+    let branch = stdout(&repo.git_in(&worktree, &["branch", "--show-current"]));
+    assert_eq!(branch.trim(), "feature-trash");
+    let status = stdout(&repo.git_in(&worktree, &["status", "--porcelain"]));
+    assert!(status.contains(" M README.md"), "{}", status);
+
+    let list = stdout(&repo.gwik(&["trash", "list"]));
+    assert!(
+        !list.contains("feature-trash"),
+        "Restored entries leave the trash"
+    );
+}
+
+/// A branch deleted after trashing is recreated at the trashed HEAD
+#[test]
+fn test_restore_recreates_deleted_branch() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-gone"]);
+    repo.commit_file_in(&repo.worktree_path("feature-gone"), "work.txt", "work\n");
+    let head = repo.rev_parse("feature-gone");
+
+    let output = repo.gwik(&["close", "feature-gone", "--trash", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    repo.git(&["branch", "-D", "feature-gone"]);
+
+    let output = repo.gwik(&["restore", "feature-gone"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.rev_parse("feature-gone"), head);
+    assert!(repo.worktree_path("feature-gone").join("work.txt").exists());
+}
+
+/// A trashed detached HEAD survives garbage collection until the entry is gone
+#[test]
+fn test_trash_pins_head() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-pinned"]);
+    let worktree = repo.worktree_path("feature-pinned");
+    repo.git_in(&worktree, &["checkout", "-q", "--detach"]);
+    repo.commit_file_in(&worktree, "only-here.txt", "only here\n");
+    let head = stdout(&repo.git_in(&worktree, &["rev-parse", "HEAD"]));
+
+    let output = repo.gwik(&["close", "feature-pinned", "--trash", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    repo.git(&["reflog", "expire", "--expire=now", "--all"]);
+    repo.git(&["gc", "-q", "--prune=now"]);
+
+    let output = repo.gwik(&["restore", "feature-pinned"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&repo.git_in(&worktree, &["rev-parse", "HEAD"])),
+        head
+    );
+    let pins = stdout(&repo.git(&["for-each-ref", "refs/gwik/trash"]));
+    assert!(pins.is_empty(), "Restoring drops the pin: {}", pins);
+}
+
+/// A branch that moved after trashing is not checked out under the restored files
+#[test]
+fn test_restore_moved_branch_detaches() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-moved"]);
+    let worktree = repo.worktree_path("feature-moved");
+    fs::write(worktree.join("README.md"), "edited\n").unwrap();
+    let head = repo.rev_parse("feature-moved");
+
+    repo.gwik(&["close", "feature-moved", "--trash"]);
+    repo.commit_file_in(&repo.root, "new.txt", "new\n");
+    repo.git(&["branch", "-f", "feature-moved", "HEAD"]);
+
+    let output = repo.gwik(&["restore", "feature-moved"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("Branch 'feature-moved' moved since it was trashed"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(
+        stdout(&repo.git_in(&worktree, &["rev-parse", "HEAD"])).trim(),
+        head
+    );
+    let status = stdout(&repo.git_in(&worktree, &["status", "--porcelain"]));
+    assert_eq!(status.trim(), "M README.md");
+}
+
+/// gwik trash empty deletes entries older than trash_expire; --all deletes everything
+#[test]
+fn test_trash_empty() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-old"]);
+    repo.gwik(&["close", "feature-old", "--trash"]);
+
+    let output = repo.gwik(&["trash", "empty"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Deleted 0 trashed worktree(s)"));
+
+    repo.write_local_config("trash_expire = \"0s\"\n");
+    let output = repo.gwik(&["trash", "empty"]);
+    assert!(stderr(&output).contains("Deleted 1 trashed worktree(s)"));
+
+    repo.gwik(&["open", "feature-new"]);
+    repo.gwik(&["close", "feature-new", "--trash"]);
+    repo.write_local_config("trash_expire = \"30d\"\n");
+    let output = repo.gwik(&["trash", "empty", "--all"]);
+    assert!(stderr(&output).contains("Deleted 1 trashed worktree(s)"));
+    assert!(stderr(&repo.gwik(&["trash", "list"])).contains("Trash is empty."));
+}
+
+/// An invalid trash_expire only fails `gwik trash empty`
+#[test]
+fn test_trash_invalid_expire() {
+    let repo = TestRepo::new();
+    repo.write_local_config("trash_expire = \"a month\"\n");
+
+    let output = repo.gwik(&["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = repo.gwik(&["trash", "empty"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Invalid trash_expire"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = repo.gwik(&["trash", "empty", "--all"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+/// Restoring an unknown name lists what is in the trash
+#[test]
+fn test_restore_unknown() {
+    let repo = TestRepo::new();
+    repo.gwik(&["open", "feature-listed"]);
+    repo.gwik(&["close", "feature-listed", "--trash"]);
+
+    let output = repo.gwik(&["restore", "feature-missing"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("No trashed worktree 'feature-missing'"),
+        "{}",
+        err
+    );
+    assert!(err.contains("  feature-listed"), "{}", err);
+}